@@ -0,0 +1,424 @@
+//! Asynchronous client
+//!
+//! `AsyncGitLab` wraps a `GitLab` and runs its requests on a thread pool, returning futures
+//! (from the `futures` crate) instead of blocking the caller. Many requests can then be in flight
+//! at the same time, and be driven from an existing event loop.
+//!
+//! The listers are the same builders as with the blocking `GitLab`: `AsyncGitLab::projects()`,
+//! `groups()`, `issues()` and `merge_requests()` return them, and once configured they are handed
+//! to `list()`, `list_paginated()` or `list_page()` (returning a future) or to `stream()`
+//! (returning a stream of all the items, fetching the pages as needed).
+//!
+//! Only the query of a lister (`BuildQuery::build_query()`) is reused: it is sent as a `GET`
+//! request and the response is deserialized into the item type of the lister's `Lister`
+//! implementation. The lister's own `list()` is never called.
+//!
+//! Any other call (`get_project()`, creating an issue, etc.) can be run on the pool with
+//! `spawn_fn()`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! extern crate futures;
+//! extern crate gitlab_api;
+//!
+//! use futures::{Future, Stream};
+//! use gitlab_api::{AsyncGitLab, GitLab};
+//!
+//! # fn main() {
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+//! let agl = AsyncGitLab::new(gl);
+//!
+//! // Both requests are sent concurrently.
+//! let projects = agl.list(agl.projects().owned().archived(false));
+//! let groups = agl.list(&agl.groups());
+//! let (projects, groups) = projects.join(groups).wait().unwrap();
+//! println!("{} projects, {} groups", projects.len(), groups.len());
+//!
+//! // All the issues, one page at a time.
+//! let issues = agl.stream(&agl.issues()).collect().wait().unwrap();
+//! println!("{} issues", issues.len());
+//!
+//! // Anything else.
+//! let project = agl.spawn_fn(|gl| gl.get_project("group", "project")).wait().unwrap();
+//! println!("{}", project.web_url);
+//! # }
+//! ```
+
+
+use std::sync::Arc;
+use std;
+
+use futures::{Async, Future, Poll, Stream};
+use futures_cpupool::{CpuFuture, CpuPool};
+use serde;
+
+use BuildQuery;
+use GitLab;
+use Lister;
+use pagination::{self, DEFAULT_PER_PAGE, Page};
+
+use ::errors::*;
+
+
+/// A request running on the pool of an `AsyncGitLab`.
+pub type GitLabFuture<T> = CpuFuture<T, Error>;
+
+
+/// Asynchronous wrapper around `GitLab`.
+///
+/// Cloning an `AsyncGitLab` is cheap: clones share the same `GitLab` and thread pool.
+#[derive(Clone)]
+pub struct AsyncGitLab {
+    gl: Arc<GitLab>,
+    pool: CpuPool,
+}
+
+
+impl std::fmt::Debug for AsyncGitLab {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "AsyncGitLab {{ {:?} }}", self.gl)
+    }
+}
+
+
+impl AsyncGitLab {
+    /// Run the requests of `gl` on a new pool, with one thread per CPU.
+    pub fn new(gl: GitLab) -> AsyncGitLab {
+        AsyncGitLab::with_pool(gl, CpuPool::new_num_cpus())
+    }
+
+    /// Run the requests of `gl` on `pool`, which can be shared with other parts of the program.
+    ///
+    /// The size of the pool bounds the number of requests in flight.
+    pub fn with_pool(gl: GitLab, pool: CpuPool) -> AsyncGitLab {
+        AsyncGitLab {
+            gl: Arc::new(gl),
+            pool: pool,
+        }
+    }
+
+    /// The blocking `GitLab` wrapped.
+    pub fn gitlab(&self) -> &GitLab {
+        &self.gl
+    }
+
+    pub fn groups(&self) -> ::groups::GroupsLister {
+        self.gl.groups()
+    }
+
+    pub fn projects(&self) -> ::projects::ProjectsLister {
+        self.gl.projects()
+    }
+
+    pub fn issues(&self) -> ::issues::IssuesLister {
+        self.gl.issues()
+    }
+
+    pub fn merge_requests(&self, project_id: i64) -> ::merge_requests::MergeRequestsLister {
+        self.gl.merge_requests(project_id)
+    }
+
+    /// Commit the lister: Query GitLab in the background and resolve to the list of items.
+    ///
+    /// Only `lister.build_query()` is used; its `Lister` implementation gives the type of the
+    /// items.
+    pub fn list<L, T>(&self, lister: &L) -> GitLabFuture<T>
+        where L: Lister<T> + BuildQuery,
+              T: serde::Deserialize + Send + 'static
+    {
+        self.get(lister.build_query(), None, None)
+    }
+
+    /// Like `list()`, for a single page.
+    pub fn list_paginated<L, T>(&self, lister: &L, page: u16, per_page: u16) -> GitLabFuture<T>
+        where L: Lister<T> + BuildQuery,
+              T: serde::Deserialize + Send + 'static
+    {
+        self.get(lister.build_query(), Some(page), Some(per_page))
+    }
+
+    /// Like `list_paginated()`, but also resolve to the pagination information sent by GitLab.
+    pub fn list_page<L, T>(&self,
+                           lister: &L,
+                           page: u16,
+                           per_page: u16)
+                           -> GitLabFuture<Page<T>>
+        where L: Lister<T> + BuildQuery,
+              T: serde::Deserialize + Send + 'static
+    {
+        let query = lister.build_query();
+        debug!("query: {:?}", query);
+
+        self.spawn_fn(move |gl| {
+            gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+        })
+    }
+
+    /// Stream all the items of the lister, fetching the next page only once the current one has
+    /// been consumed.
+    ///
+    /// This is the asynchronous counterpart of `Lister::iter()`.
+    pub fn stream<L, T>(&self, lister: &L) -> ListerStream<T>
+        where L: Lister<Vec<T>> + BuildQuery,
+              T: serde::Deserialize + Send + 'static
+    {
+        ListerStream {
+            agl: self.clone(),
+            query: lister.build_query(),
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+            items: Vec::new().into_iter(),
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// Run `f` on the pool, with the wrapped `GitLab`.
+    pub fn spawn_fn<F, R>(&self, f: F) -> GitLabFuture<R>
+        where F: FnOnce(&GitLab) -> Result<R> + Send + 'static,
+              R: Send + 'static
+    {
+        let gl = self.gl.clone();
+        self.pool.spawn_fn(move || f(&gl))
+    }
+
+    fn get<T>(&self, query: String, page: Option<u16>, per_page: Option<u16>) -> GitLabFuture<T>
+        where T: serde::Deserialize + Send + 'static
+    {
+        debug!("query: {:?}", query);
+
+        self.spawn_fn(move |gl| {
+            gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+        })
+    }
+}
+
+
+/// Stream over all items of a lister, fetching pages lazily.
+///
+/// Like `pagination::ListerIter`, the stream ends after the last page and ends after the first
+/// error.
+pub struct ListerStream<T> {
+    agl: AsyncGitLab,
+    query: String,
+    page: u16,
+    per_page: u16,
+    items: std::vec::IntoIter<T>,
+    pending: Option<GitLabFuture<Page<Vec<T>>>>,
+    done: bool,
+}
+
+
+impl<T> ListerStream<T>
+    where T: serde::Deserialize + Send + 'static
+{
+    /// Number of items to request for each page (bounded between 1 and 100).
+    pub fn per_page(mut self, per_page: u16) -> Self {
+        self.per_page = pagination::clamp_per_page(per_page);
+        self
+    }
+
+    fn fetch_page(&self) -> GitLabFuture<Page<Vec<T>>> {
+        let query = self.query.clone();
+        let (page, per_page) = (self.page, self.per_page);
+        debug!("query: {:?}, page: {}", query, page);
+
+        self.agl.spawn_fn(move |gl| {
+            gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+        })
+    }
+}
+
+
+impl<T> std::fmt::Debug for ListerStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ListerStream")
+            .field("query", &self.query)
+            .field("page", &self.page)
+            .field("per_page", &self.per_page)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+
+impl<T> Stream for ListerStream<T>
+    where T: serde::Deserialize + Send + 'static
+{
+    type Item = T;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<T>, Error> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Ok(Async::Ready(Some(item)));
+            }
+
+            if self.done {
+                return Ok(Async::Ready(None));
+            }
+
+            let polled = match self.pending {
+                Some(ref mut pending) => pending.poll(),
+                None => {
+                    self.pending = Some(self.fetch_page());
+                    continue;
+                }
+            };
+
+            match polled {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(page)) => {
+                    self.pending = None;
+                    self.done = page.is_last(self.per_page);
+                    self.page += 1;
+                    self.items = page.items.into_iter();
+                }
+                Err(e) => {
+                    self.pending = None;
+                    self.done = true;
+                    return Err(e).chain_err(|| format!("cannot get page {}", self.page));
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use futures::{Future, Stream};
+    use futures::future::join_all;
+    use futures_cpupool::CpuPool;
+
+    use async_gitlab::*;
+    use fixtures::groups_page;
+    use transport::{MockTransport, Response};
+
+
+    fn async_gitlab(mock: &MockTransport) -> AsyncGitLab {
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        AsyncGitLab::with_pool(gl, CpuPool::new(4))
+    }
+
+
+    #[test]
+    fn list_same_query_as_blocking() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2)));
+        let agl = async_gitlab(&mock);
+
+        let groups = agl.list(&agl.groups().owned()).wait().unwrap();
+        assert_eq!(groups.len(), 2);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, "https://gitlab.example.com/api/v3/groups/owned");
+    }
+
+
+    #[test]
+    fn list_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(3, 2))
+            .with_header("X-Page", "2")
+            .with_header("X-Next-Page", "3"));
+        let agl = async_gitlab(&mock);
+
+        let page = agl.list_page(&agl.groups(), 2, 2).wait().unwrap();
+        assert_eq!(page.items[0].id, 3);
+        assert_eq!(page.pagination.next_page, Some(3));
+        assert!(mock.requests()[0].url.ends_with("groups?page=2&per_page=2"));
+    }
+
+
+    #[test]
+    fn concurrent_requests() {
+        let mock = MockTransport::new();
+        for _ in 0..8 {
+            mock.push_response(Response::new(200, &groups_page(1, 1)));
+        }
+        let agl = async_gitlab(&mock);
+
+        let futures: Vec<_> = (0..8).map(|_| agl.list(&agl.groups())).collect();
+        let results = join_all(futures).wait().unwrap();
+        assert_eq!(results.len(), 8);
+        assert_eq!(mock.requests().len(), 8);
+    }
+
+
+    #[test]
+    fn list_error() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(404, r#"{"message": "404 Not Found"}"#));
+        let agl = async_gitlab(&mock);
+
+        let e = agl.list(&agl.merge_requests(42)).wait().unwrap_err();
+        assert_eq!(e.to_string(), "cannot get query projects/42/merge_requests");
+        assert!(e.iter().any(|cause| cause.to_string() == "not found: 404 Not Found"));
+    }
+
+
+    #[test]
+    fn stream_all_pages() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2)));
+        mock.push_response(Response::new(200, &groups_page(3, 2)));
+        mock.push_response(Response::new(200, &groups_page(5, 1)));
+        let agl = async_gitlab(&mock);
+
+        let groups = agl.stream(&agl.groups()).per_page(2).collect().wait().unwrap();
+        let ids: Vec<i64> = groups.iter().map(|group| group.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].url.ends_with("page=3&per_page=2"));
+    }
+
+
+    #[test]
+    fn stream_trusts_next_page_over_short_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 1))
+            .with_header("X-Page", "1")
+            .with_header("X-Next-Page", "2"));
+        mock.push_response(Response::new(200, &groups_page(2, 2))
+            .with_header("X-Page", "2")
+            .with_header("X-Next-Page", ""));
+        let agl = async_gitlab(&mock);
+
+        let groups = agl.stream(&agl.groups()).per_page(2).collect().wait().unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+
+    #[test]
+    fn stream_stops_on_error() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2)));
+        mock.push_response(Response::new(500, ""));
+        let agl = async_gitlab(&mock);
+
+        let results: Vec<_> = agl.stream(&agl.groups()).per_page(2).wait().collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[..2].iter().all(|result| result.is_ok()));
+        assert!(results[2].is_err());
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+
+    #[test]
+    fn spawn_fn() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#));
+        let agl = async_gitlab(&mock);
+
+        let version = agl.spawn_fn(|gl| gl.version()).wait().unwrap();
+        assert_eq!(version.version, "8.16.4");
+    }
+}
@@ -0,0 +1,236 @@
+//! Date and time types used by the models
+//!
+//! GitLab sends timestamps (`created_at`, `updated_at`, etc.) as RFC 3339 strings
+//! (`2017-02-28T14:32:00.000Z`) and due dates as `YYYY-MM-DD` strings (`2017-03-11`).
+//!
+//! By default, they are kept as `String`s. With the optional `chrono` cargo feature, timestamps
+//! are parsed into `DateTime` (a `chrono::DateTime<chrono::Utc>`) and due dates into `Date` (a
+//! `chrono::NaiveDate`), allowing them to be sorted and compared directly:
+//!
+//! ```text
+//! [dependencies]
+//! gitlab-api = { version = "0.6", features = ["chrono"] }
+//! ```
+//!
+//! The submodules are meant to be used with serde's `with` attribute on the models' fields.
+
+
+#[cfg(feature = "chrono")]
+use chrono;
+
+
+/// A timestamp, as sent by GitLab in RFC 3339 format.
+#[cfg(feature = "chrono")]
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+/// A timestamp, as sent by GitLab in RFC 3339 format.
+#[cfg(not(feature = "chrono"))]
+pub type DateTime = String;
+
+/// A date, as sent by GitLab in `YYYY-MM-DD` format.
+#[cfg(feature = "chrono")]
+pub type Date = chrono::NaiveDate;
+/// A date, as sent by GitLab in `YYYY-MM-DD` format.
+#[cfg(not(feature = "chrono"))]
+pub type Date = String;
+
+
+/// Format of dates sent by GitLab.
+#[cfg(feature = "chrono")]
+const DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+
+#[cfg(feature = "chrono")]
+fn parse_datetime(s: &str) -> ::std::result::Result<DateTime, String> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|datetime| datetime.with_timezone(&chrono::Utc))
+        .map_err(|e| format!("invalid RFC 3339 timestamp '{}': {}", s, e))
+}
+
+#[cfg(not(feature = "chrono"))]
+fn parse_datetime(s: &str) -> ::std::result::Result<DateTime, String> {
+    Ok(s.to_string())
+}
+
+#[cfg(feature = "chrono")]
+fn format_datetime(datetime: &DateTime) -> String {
+    datetime.to_rfc3339()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn format_datetime(datetime: &DateTime) -> String {
+    datetime.clone()
+}
+
+#[cfg(feature = "chrono")]
+fn parse_date(s: &str) -> ::std::result::Result<Date, String> {
+    chrono::NaiveDate::parse_from_str(s, DATE_FORMAT)
+        .map_err(|e| format!("invalid YYYY-MM-DD date '{}': {}", s, e))
+}
+
+#[cfg(not(feature = "chrono"))]
+fn parse_date(s: &str) -> ::std::result::Result<Date, String> {
+    Ok(s.to_string())
+}
+
+#[cfg(feature = "chrono")]
+fn format_date(date: &Date) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn format_date(date: &Date) -> String {
+    date.clone()
+}
+
+
+/// (De)serialize a `DateTime`.
+pub mod datetime {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    use super::DateTime;
+
+    pub fn serialize<S>(datetime: &DateTime, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_str(&super::format_datetime(datetime))
+    }
+
+    pub fn deserialize<D>(deserializer: D) -> Result<DateTime, D::Error>
+        where D: Deserializer
+    {
+        let s = String::deserialize(deserializer)?;
+        super::parse_datetime(&s).map_err(D::Error::custom)
+    }
+}
+
+
+/// (De)serialize an `Option<DateTime>`, `null` being `None`.
+pub mod optional_datetime {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    use super::DateTime;
+
+    pub fn serialize<S>(datetime: &Option<DateTime>, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *datetime {
+            Some(ref datetime) => serializer.serialize_some(&super::format_datetime(datetime)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<D>(deserializer: D) -> Result<Option<DateTime>, D::Error>
+        where D: Deserializer
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => super::parse_datetime(&s).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+
+/// (De)serialize an `Option<Date>`, `null` being `None`.
+pub mod optional_date {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    use super::Date;
+
+    pub fn serialize<S>(date: &Option<Date>, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *date {
+            Some(ref date) => serializer.serialize_some(&super::format_date(date)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<D>(deserializer: D) -> Result<Option<Date>, D::Error>
+        where D: Deserializer
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => super::parse_date(&s).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::{Date, DateTime};
+
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Dated {
+        #[serde(with = "::dates::datetime")]
+        created_at: DateTime,
+        #[serde(default, with = "::dates::optional_datetime")]
+        updated_at: Option<DateTime>,
+        #[serde(default, with = "::dates::optional_date")]
+        due_date: Option<Date>,
+    }
+
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"created_at":"2017-02-28T14:32:00+00:00","updated_at":null,"#.to_string() +
+                   r#""due_date":"2017-03-11"}"#;
+
+        let dated: Dated = serde_json::from_str(&json).unwrap();
+        assert!(dated.updated_at.is_none());
+        assert_eq!(serde_json::to_string(&dated).unwrap(), json);
+    }
+
+
+    #[test]
+    fn missing_optional_fields() {
+        let dated: Dated = serde_json::from_str(r#"{"created_at":"2017-02-28T14:32:00.000Z"}"#)
+            .unwrap();
+        assert!(dated.updated_at.is_none());
+        assert!(dated.due_date.is_none());
+    }
+
+
+    #[cfg(not(feature = "chrono"))]
+    #[test]
+    fn strings_kept_as_is() {
+        let json = r#"{"created_at":"2017-02-28T14:32:00.000Z","due_date":"2017-03-11"}"#;
+
+        let dated: Dated = serde_json::from_str(json).unwrap();
+        assert_eq!(dated.created_at, "2017-02-28T14:32:00.000Z");
+        assert_eq!(dated.due_date, Some(String::from("2017-03-11")));
+    }
+
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parsed_with_chrono() {
+        use chrono::{Datelike, Timelike};
+
+        let json = r#"{"created_at":"2017-02-28T09:32:00.000-05:00",
+                       "updated_at":"2017-03-01T14:32:00.000Z","due_date":"2017-03-11"}"#;
+
+        let dated: Dated = serde_json::from_str(json).unwrap();
+        assert_eq!(dated.created_at.hour(), 14);
+        let updated_at = dated.updated_at.unwrap();
+        assert_eq!((updated_at - dated.created_at).num_hours(), 24);
+        let due_date = dated.due_date.unwrap();
+        assert_eq!((due_date.year(), due_date.month(), due_date.day()), (2017, 3, 11));
+    }
+
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn invalid_formats() {
+        assert!(serde_json::from_str::<Dated>(r#"{"created_at":"2017-02-28"}"#).is_err());
+        assert!(serde_json::from_str::<Dated>(r#"{"created_at":"2017-02-28T14:32:00.000Z",
+                                                  "due_date":"11/03/2017"}"#)
+            .is_err());
+    }
+}
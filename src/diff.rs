@@ -0,0 +1,688 @@
+//! Parsing unified diffs
+//!
+//! GitLab sends unified diffs in the `diff` of the changes of merge requests, of commits and of
+//! comparisons. For each file, it only sends the hunks:
+//!
+//! ```text
+//! @@ -1,4 +1,4 @@ fn main() {
+//!  fn main() {
+//! -    println!("Hello");
+//! +    println!("Hello, world!");
+//!  }
+//! ```
+//!
+//! `FileDiff::parse()` parses such a diff into its hunks and lines, numbering every line as it is
+//! before (`old_line`) and after (`new_line`) the change. `Patch::parse()` parses a diff of
+//! several files, as output by `git diff`, each file starting with its header (`diff --git`,
+//! `---`, `+++`...).
+//!
+//! Both are rendered back to the text they were parsed from by `Display`, with the exception of
+//! empty context lines: some tools strip their leading space, they are rendered with it.
+//!
+//! The `old_line` and `new_line` of a line are the ones a diff note on that line takes
+//! (`::NotePosition`): an added line only has a `new_line`, a removed one only an `old_line` and
+//! a context line both.
+//!
+//! # Examples
+//!
+//! ```
+//! use gitlab_api::diff::{FileDiff, LineKind};
+//!
+//! let diff = FileDiff::parse("@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n").unwrap();
+//!
+//! let stats = diff.stats();
+//! assert_eq!((stats.additions, stats.deletions), (1, 1));
+//!
+//! let line = diff.line_at_new(2).unwrap();
+//! assert_eq!(line.kind, LineKind::Added);
+//! assert_eq!(line.content, "B");
+//! assert_eq!((line.old_line, line.new_line), (None, Some(2)));
+//!
+//! assert_eq!(diff.to_string(), "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+//! ```
+
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::errors::*;
+
+
+const NO_NEWLINE_AT_END: &'static str = "\\ No newline at end of file";
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineKind {
+    /// Unchanged line (` `)
+    Context,
+    /// Added line (`+`)
+    Added,
+    /// Removed line (`-`)
+    Removed,
+}
+
+
+/// A line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub kind: LineKind,
+    /// The line, without its `+`, `-` or ` ` prefix and its newline
+    pub content: String,
+    /// Number of the line before the change, `None` for an added line
+    pub old_line: Option<i64>,
+    /// Number of the line after the change, `None` for a removed line
+    pub new_line: Option<i64>,
+    /// The line is the last of its file and has no newline (`\ No newline at end of file`)
+    pub no_newline_at_end: bool,
+}
+
+
+/// A hunk: a range of changed lines along with their context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: i64,
+    pub old_lines: i64,
+    pub new_start: i64,
+    pub new_lines: i64,
+    /// The text following the range in the hunk header, usually the enclosing function
+    pub section: String,
+    pub lines: Vec<Line>,
+}
+
+
+/// The diff of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// The lines preceding the first hunk (`diff --git`, `index`, `---`, `+++`...). Empty for the
+    /// diffs sent by GitLab.
+    pub header: Vec<String>,
+    /// Path before the change, `None` for a new file or when the header does not tell
+    pub old_path: Option<String>,
+    /// Path after the change, `None` for a deleted file or when the header does not tell
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+
+/// The diff of several files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub files: Vec<FileDiff>,
+}
+
+
+/// Number of lines added and removed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+
+impl Hunk {
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        for line in &self.lines {
+            match line.kind {
+                LineKind::Added => stats.additions += 1,
+                LineKind::Removed => stats.deletions += 1,
+                LineKind::Context => {}
+            }
+        }
+        stats
+    }
+}
+
+
+impl FileDiff {
+    /// Parse the diff of a single file: its hunks, optionally preceded by a header.
+    pub fn parse(diff: &str) -> Result<FileDiff> {
+        let lines = split_lines(diff);
+        let (file, next) = parse_file(&lines, 0)?;
+        if next < lines.len() {
+            return Err(invalid(next, "expected a hunk header"));
+        }
+        Ok(file)
+    }
+
+    pub fn stats(&self) -> Stats {
+        sum_stats(self.hunks.iter().map(Hunk::stats))
+    }
+
+    /// The line numbered `old_line` before the change, if it is part of a hunk.
+    pub fn line_at_old(&self, old_line: i64) -> Option<&Line> {
+        for hunk in &self.hunks {
+            for line in &hunk.lines {
+                if line.old_line == Some(old_line) {
+                    return Some(line);
+                }
+            }
+        }
+        None
+    }
+
+    /// The line numbered `new_line` after the change, if it is part of a hunk.
+    pub fn line_at_new(&self, new_line: i64) -> Option<&Line> {
+        for hunk in &self.hunks {
+            for line in &hunk.lines {
+                if line.new_line == Some(new_line) {
+                    return Some(line);
+                }
+            }
+        }
+        None
+    }
+}
+
+
+impl Patch {
+    /// Parse the diff of several files, each one starting with its header.
+    pub fn parse(patch: &str) -> Result<Patch> {
+        let lines = split_lines(patch);
+        let mut files = Vec::new();
+        let mut next = 0;
+        while next < lines.len() {
+            if !files.is_empty() && !is_file_start(lines[next]) {
+                return Err(invalid(next, "expected a hunk header or the header of a file"));
+            }
+            let (file, after) = parse_file(&lines, next)?;
+            files.push(file);
+            next = after;
+        }
+        Ok(Patch { files: files })
+    }
+
+    pub fn stats(&self) -> Stats {
+        sum_stats(self.files.iter().map(FileDiff::stats))
+    }
+}
+
+
+impl FromStr for FileDiff {
+    type Err = Error;
+
+    fn from_str(diff: &str) -> Result<FileDiff> {
+        FileDiff::parse(diff)
+    }
+}
+
+
+impl FromStr for Patch {
+    type Err = Error;
+
+    fn from_str(patch: &str) -> Result<Patch> {
+        Patch::parse(patch)
+    }
+}
+
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match self.kind {
+            LineKind::Context => ' ',
+            LineKind::Added => '+',
+            LineKind::Removed => '-',
+        };
+        writeln!(f, "{}{}", prefix, self.content)?;
+        if self.no_newline_at_end {
+            writeln!(f, "{}", NO_NEWLINE_AT_END)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Format as in unified diffs, where a count of `1` is omitted: `@@ -1 +1,2 @@`.
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let range = |start: i64, lines: i64| if lines == 1 {
+            start.to_string()
+        } else {
+            format!("{},{}", start, lines)
+        };
+        write!(f,
+               "@@ -{} +{} @@",
+               range(self.old_start, self.old_lines),
+               range(self.new_start, self.new_lines))?;
+        if !self.section.is_empty() {
+            write!(f, " {}", self.section)?;
+        }
+        writeln!(f)?;
+        for line in &self.lines {
+            write!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+
+impl fmt::Display for FileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.header {
+            writeln!(f, "{}", line)?;
+        }
+        for hunk in &self.hunks {
+            write!(f, "{}", hunk)?;
+        }
+        Ok(())
+    }
+}
+
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for file in &self.files {
+            write!(f, "{}", file)?;
+        }
+        Ok(())
+    }
+}
+
+
+fn invalid(index: usize, reason: &str) -> Error {
+    ErrorKind::InvalidDiff(index + 1, String::from(reason)).into()
+}
+
+
+fn sum_stats<I>(stats: I) -> Stats
+    where I: Iterator<Item = Stats>
+{
+    stats.fold(Stats::default(), |sum, stats| {
+        Stats {
+            additions: sum.additions + stats.additions,
+            deletions: sum.deletions + stats.deletions,
+        }
+    })
+}
+
+
+/// Split the text in lines, without their newline. A final newline does not end an empty line.
+fn split_lines(text: &str) -> Vec<&str> {
+    let text = if text.ends_with('\n') { &text[..text.len() - 1] } else { text };
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split('\n').collect()
+    }
+}
+
+
+fn is_file_start(line: &str) -> bool {
+    line.starts_with("diff --git ") || line.starts_with("--- ")
+}
+
+
+/// Parse the file starting at `lines[start]`, returning it and the index of the line following
+/// it.
+fn parse_file(lines: &[&str], start: usize) -> Result<(FileDiff, usize)> {
+    let mut header: Vec<String> = Vec::new();
+    let mut next = start;
+    while next < lines.len() && !lines[next].starts_with("@@") {
+        let line = lines[next];
+        // A file without hunks (binary, renamed, mode change...) is followed by the next file.
+        let has_old_marker = header.iter().any(|header_line| header_line.starts_with("--- "));
+        if next > start &&
+           (line.starts_with("diff --git ") || (line.starts_with("--- ") && has_old_marker)) {
+            break;
+        }
+        header.push(String::from(line));
+        next += 1;
+    }
+
+    let mut hunks = Vec::new();
+    while next < lines.len() && lines[next].starts_with("@@") {
+        let (hunk, after) = parse_hunk(lines, next)?;
+        hunks.push(hunk);
+        next = after;
+    }
+
+    let (old_path, new_path) = header_paths(&header);
+    let file = FileDiff {
+        header: header,
+        old_path: old_path,
+        new_path: new_path,
+        hunks: hunks,
+    };
+    Ok((file, next))
+}
+
+
+/// Parse the hunk whose header is `lines[start]`, returning it and the index of the line
+/// following it.
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize)> {
+    let mut hunk = parse_hunk_header(lines[start])
+        .ok_or_else(|| invalid(start, "invalid hunk header"))?;
+    let mut old_remaining = hunk.old_lines;
+    let mut new_remaining = hunk.new_lines;
+    let mut old_line = hunk.old_start;
+    let mut new_line = hunk.new_start;
+
+    let mut next = start + 1;
+    while next < lines.len() {
+        let line = lines[next];
+        if line.starts_with('\\') {
+            match hunk.lines.last_mut() {
+                Some(last) => last.no_newline_at_end = true,
+                None => return Err(invalid(next, "no line before the end of file marker")),
+            }
+            next += 1;
+            continue;
+        }
+        if old_remaining == 0 && new_remaining == 0 {
+            break;
+        }
+
+        let (kind, content) = match line.chars().next() {
+            Some(' ') => (LineKind::Context, &line[1..]),
+            // An empty context line, whose space was stripped
+            None => (LineKind::Context, ""),
+            Some('+') => (LineKind::Added, &line[1..]),
+            Some('-') => (LineKind::Removed, &line[1..]),
+            Some(_) => return Err(invalid(next, "expected a context, added or removed line")),
+        };
+        let (old, new) = match kind {
+            LineKind::Context if old_remaining > 0 && new_remaining > 0 => {
+                (Some(old_line), Some(new_line))
+            }
+            LineKind::Added if new_remaining > 0 => (None, Some(new_line)),
+            LineKind::Removed if old_remaining > 0 => (Some(old_line), None),
+            _ => return Err(invalid(next, "more lines than the hunk header counts")),
+        };
+        if old.is_some() {
+            old_line += 1;
+            old_remaining -= 1;
+        }
+        if new.is_some() {
+            new_line += 1;
+            new_remaining -= 1;
+        }
+        hunk.lines.push(Line {
+            kind: kind,
+            content: String::from(content),
+            old_line: old,
+            new_line: new,
+            no_newline_at_end: false,
+        });
+        next += 1;
+    }
+
+    if old_remaining > 0 || new_remaining > 0 {
+        return Err(invalid(next, "fewer lines than the hunk header counts"));
+    }
+    Ok((hunk, next))
+}
+
+
+/// Parse `@@ -OLD_START[,OLD_LINES] +NEW_START[,NEW_LINES] @@[ SECTION]`.
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    if !line.starts_with("@@ -") {
+        return None;
+    }
+    let rest = &line[4..];
+    let end = match rest.find(" @@") {
+        Some(end) => end,
+        None => return None,
+    };
+    let ranges: Vec<&str> = rest[..end].split(" +").collect();
+    if ranges.len() != 2 {
+        return None;
+    }
+    let (old, new) = match (parse_range(ranges[0]), parse_range(ranges[1])) {
+        (Some(old), Some(new)) => (old, new),
+        _ => return None,
+    };
+    let section = &rest[end + 3..];
+    let section = if section.starts_with(' ') { &section[1..] } else { section };
+
+    Some(Hunk {
+        old_start: old.0,
+        old_lines: old.1,
+        new_start: new.0,
+        new_lines: new.1,
+        section: String::from(section),
+        lines: Vec::new(),
+    })
+}
+
+
+/// Parse `START[,LINES]`, where `LINES` defaults to `1`. The numbers of the lines of the range
+/// must not overflow.
+fn parse_range(range: &str) -> Option<(i64, i64)> {
+    let mut parts = range.splitn(2, ',');
+    let start: Option<i64> = parts.next().and_then(|start| start.parse().ok());
+    let lines = match parts.next() {
+        Some(lines) => lines.parse().ok(),
+        None => Some(1),
+    };
+    match (start, lines) {
+        (Some(start), Some(lines)) if start >= 0 && lines >= 0 => {
+            start.checked_add(lines).map(|_| (start, lines))
+        }
+        _ => None,
+    }
+}
+
+
+/// The paths before and after the change, from the `---`/`+++` lines of the header, or else
+/// from its `rename from`/`rename to` or `diff --git` lines.
+fn header_paths(header: &[String]) -> (Option<String>, Option<String>) {
+    let mut markers = (None, None);
+    let mut renames = (None, None);
+    let mut git = (None, None);
+    let mut new_file = false;
+    let mut deleted_file = false;
+
+    for line in header {
+        if line.starts_with("--- ") {
+            markers.0 = Some(marker_path(&line[4..], "a/"));
+        } else if line.starts_with("+++ ") {
+            markers.1 = Some(marker_path(&line[4..], "b/"));
+        } else if line.starts_with("rename from ") {
+            renames.0 = Some(String::from(&line[12..]));
+        } else if line.starts_with("rename to ") {
+            renames.1 = Some(String::from(&line[10..]));
+        } else if line.starts_with("new file mode ") {
+            new_file = true;
+        } else if line.starts_with("deleted file mode ") {
+            deleted_file = true;
+        } else if line.starts_with("diff --git a/") {
+            // Ambiguous if the paths contain " b/": the other lines are preferred.
+            if let Some(separator) = line.find(" b/") {
+                git = (Some(String::from(&line[13..separator])),
+                       Some(String::from(&line[separator + 3..])));
+            }
+        }
+    }
+
+    let old_path = match markers.0 {
+        Some(path) => path,
+        None if new_file => None,
+        None => renames.0.or(git.0),
+    };
+    let new_path = match markers.1 {
+        Some(path) => path,
+        None if deleted_file => None,
+        None => renames.1.or(git.1),
+    };
+    (old_path, new_path)
+}
+
+
+/// The path of a `---` or `+++` line, without its timestamp and its `a/` or `b/` prefix.
+fn marker_path(marker: &str, prefix: &str) -> Option<String> {
+    let path = marker.split('\t').next().unwrap_or(marker);
+    if path == "/dev/null" {
+        None
+    } else if path.starts_with(prefix) {
+        Some(String::from(&path[prefix.len()..]))
+    } else {
+        Some(String::from(path))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use diff::*;
+
+
+    const GITLAB_DIFF: &'static str = "@@ -1,4 +1,5 @@ mod a\n use std::fmt;\n-use std::io;\n\
+                                       +use std::io::{self, Read};\n+use std::str;\n \n \
+                                       fn main() {\n@@ -20,3 +21,2 @@ fn main() {\n     \
+                                       let a = 1;\n-    let b = 2;\n }\n";
+
+    const PATCH: &'static str = "diff --git a/README.md b/README.md\n\
+                                 index 7a3f1c9..8b2e4d0 100644\n\
+                                 --- a/README.md\n\
+                                 +++ b/README.md\n\
+                                 @@ -1 +1 @@\n\
+                                 -Hello\n\
+                                 +Hello, world!\n\
+                                 \\ No newline at end of file\n\
+                                 diff --git a/old.txt b/new.txt\n\
+                                 similarity index 100%\n\
+                                 rename from old.txt\n\
+                                 rename to new.txt\n\
+                                 diff --git a/logo.png b/logo.png\n\
+                                 new file mode 100644\n\
+                                 index 0000000..3c4d5e6\n\
+                                 Binary files /dev/null and b/logo.png differ\n\
+                                 diff --git a/src/gone.rs b/src/gone.rs\n\
+                                 deleted file mode 100644\n\
+                                 index 1a2b3c4..0000000\n\
+                                 --- a/src/gone.rs\n\
+                                 +++ /dev/null\n\
+                                 @@ -1,2 +0,0 @@\n\
+                                 -fn gone() {\n\
+                                 -}\n";
+
+
+    fn line(kind: LineKind, content: &str, old_line: Option<i64>, new_line: Option<i64>) -> Line {
+        Line {
+            kind: kind,
+            content: String::from(content),
+            old_line: old_line,
+            new_line: new_line,
+            no_newline_at_end: false,
+        }
+    }
+
+
+    #[test]
+    fn parse_gitlab_diff() {
+        let diff = FileDiff::parse(GITLAB_DIFF).unwrap();
+
+        assert!(diff.header.is_empty());
+        assert_eq!(diff.old_path, None);
+        assert_eq!(diff.hunks.len(), 2);
+
+        let hunk = &diff.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines),
+                   (1, 4, 1, 5));
+        assert_eq!(hunk.section, "mod a");
+        assert_eq!(hunk.lines,
+                   vec![line(LineKind::Context, "use std::fmt;", Some(1), Some(1)),
+                        line(LineKind::Removed, "use std::io;", Some(2), None),
+                        line(LineKind::Added, "use std::io::{self, Read};", None, Some(2)),
+                        line(LineKind::Added, "use std::str;", None, Some(3)),
+                        line(LineKind::Context, "", Some(3), Some(4)),
+                        line(LineKind::Context, "fn main() {", Some(4), Some(5))]);
+
+        let hunk = &diff.hunks[1];
+        assert_eq!(hunk.section, "fn main() {");
+        assert_eq!(hunk.lines[1], line(LineKind::Removed, "    let b = 2;", Some(21), None));
+        assert_eq!(hunk.lines[2], line(LineKind::Context, "}", Some(22), Some(22)));
+
+        assert_eq!(diff.stats(),
+                   Stats {
+                       additions: 2,
+                       deletions: 2,
+                   });
+        assert_eq!(diff.to_string(), GITLAB_DIFF);
+    }
+
+
+    #[test]
+    fn parse_patch() {
+        let patch: Patch = PATCH.parse().unwrap();
+
+        let paths: Vec<_> = patch.files
+            .iter()
+            .map(|file| (file.old_path.as_ref().map(String::as_str),
+                         file.new_path.as_ref().map(String::as_str)))
+            .collect();
+        assert_eq!(paths,
+                   vec![(Some("README.md"), Some("README.md")),
+                        (Some("old.txt"), Some("new.txt")),
+                        (None, Some("logo.png")),
+                        (Some("src/gone.rs"), None)]);
+
+        let readme = &patch.files[0];
+        assert_eq!(readme.header.len(), 4);
+        assert_eq!((readme.hunks[0].old_lines, readme.hunks[0].new_lines), (1, 1));
+        assert!(!readme.hunks[0].lines[0].no_newline_at_end);
+        assert!(readme.hunks[0].lines[1].no_newline_at_end);
+        assert!(patch.files[1].hunks.is_empty());
+        assert!(patch.files[2].hunks.is_empty());
+        assert_eq!(patch.files[3].hunks[0].new_start, 0);
+
+        assert_eq!(patch.stats(),
+                   Stats {
+                       additions: 1,
+                       deletions: 3,
+                   });
+        assert_eq!(patch.to_string(), PATCH);
+    }
+
+
+    #[test]
+    fn parse_plain_diff() {
+        let text = "--- a.txt\t2017-03-01 10:00:00.000000000 +0100\n\
+                    +++ b.txt\t2017-03-02 10:00:00.000000000 +0100\n\
+                    @@ -0,0 +1,2 @@\n+one\n+two\n\
+                    --- c.txt\n+++ c.txt\n@@ -3 +3 @@\n-three\n+THREE\n";
+        let patch = Patch::parse(text).unwrap();
+
+        assert_eq!(patch.files.len(), 2);
+        assert_eq!(patch.files[0].old_path, Some(String::from("a.txt")));
+        assert_eq!(patch.files[0].new_path, Some(String::from("b.txt")));
+        assert_eq!(patch.files[0].hunks[0].lines[1].new_line, Some(2));
+        assert_eq!(patch.files[1].line_at_old(3).unwrap().content, "three");
+        assert_eq!(patch.files[1].line_at_new(3).unwrap().content, "THREE");
+        assert!(patch.files[1].line_at_new(4).is_none());
+        assert_eq!(patch.to_string(), text);
+    }
+
+
+    #[test]
+    fn parse_lenient() {
+        // Empty context line without its space, and a hunk header without section
+        let diff = FileDiff::parse("@@ -1,2 +1,2 @@\n\n-a\n+b").unwrap();
+
+        assert_eq!(diff.hunks[0].lines[0], line(LineKind::Context, "", Some(1), Some(1)));
+        assert_eq!(diff.to_string(), "@@ -1,2 +1,2 @@\n \n-a\n+b\n");
+
+        assert_eq!(FileDiff::parse("").unwrap().hunks.len(), 0);
+    }
+
+
+    #[test]
+    fn parse_errors() {
+        let error_line = |text: &str| match FileDiff::parse(text) {
+            Err(Error(ErrorKind::InvalidDiff(line, _), _)) => line,
+            other => panic!("unexpected result for {:?}: {:?}", text, other),
+        };
+
+        assert_eq!(error_line("@@ -1,2 +1 @\n a\n"), 1);
+        assert_eq!(error_line("@@ -a +1 @@\n a\n"), 1);
+        assert_eq!(error_line("@@ -1,2 +1,2 @@\n a\n-b\n"), 4);
+        assert_eq!(error_line("@@ -1 +1 @@\n a\n+b\n"), 3);
+        assert_eq!(error_line("@@ -1 +1 @@\n*a\n"), 2);
+        assert_eq!(error_line("@@ -1 +1 @@\n a\ntrailing\n"), 3);
+        assert_eq!(error_line("@@ -0,0 +0,0 @@\n\\ No newline at end of file\n"), 2);
+        assert_eq!(error_line("@@ -9223372036854775807 +1 @@\n a\n"), 1);
+        assert_eq!(error_line("@@ -1 +9223372036854775000,1000 @@\n a\n"), 1);
+
+        assert!(Patch::parse("--- a\n+++ b\n@@ -1 +1 @@\n-a\n+b\ngarbage\n").is_err());
+    }
+}
@@ -0,0 +1,519 @@
+//! List events
+//!
+//! https://docs.gitlab.com/ce/api/events.html
+//!
+//! # List currently authenticated user's events
+//!
+//! Get a list of events for the authenticated user, sorted from newest to oldest.
+//!
+//! ```text
+//! GET /events
+//! ```
+//!
+//! This endpoint only exists with API v4: with API v3, GitLab answers `404 Not Found`. Use the
+//! events of a project (`GitLab::projects().id(...).events()`) instead, which accept the same
+//! filters with API v4.
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `action` | string | no | Include only events of a particular action type |
+//! | `target_type` | string | no | Include only events of a particular target type |
+//! | `before` | date | no | Include only events created before a particular date, in the format `YYYY-MM-DD` |
+//! | `after` | date | no | Include only events created after a particular date, in the format `YYYY-MM-DD` |
+//! | `sort` | string | no | Sort events in `asc` or `desc` order by `created_at`. Default is `desc` |
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gitlab_api::{ApiVersion, GitLab, Lister};
+//! use gitlab_api::events::{EventAction, ListingAction};
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .api_version(ApiVersion::V4);
+//!
+//! let after = "2017-02-28".parse().unwrap();
+//! for event in gl.events().action(ListingAction::Pushed).after(after).iter() {
+//!     let event = event.unwrap();
+//!     if event.action_name == EventAction::PushedTo {
+//!         println!("{:?}", event.push_data);
+//!     }
+//! }
+//! ```
+
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json;
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+/// Action of an event, as sent by GitLab in `action_name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventAction {
+    Created,
+    Updated,
+    Opened,
+    Closed,
+    Reopened,
+    /// Push of commits to an existing branch or tag
+    PushedTo,
+    /// Push of a new branch or tag
+    PushedNew,
+    /// Deletion of a branch or tag
+    Deleted,
+    /// A note was added
+    CommentedOn,
+    /// A merge request was merged
+    Accepted,
+    Joined,
+    Left,
+    Destroyed,
+    Expired,
+    /// Any action unknown to this crate
+    Other(String),
+}
+
+
+/// Type of the target of an event, as sent by GitLab in `target_type`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetType {
+    Issue,
+    MergeRequest,
+    Milestone,
+    Note,
+    DiffNote,
+    DiscussionNote,
+    Project,
+    Snippet,
+    User,
+    /// Any type unknown to this crate
+    Other(String),
+}
+
+
+/// What happened in a push event, as sent by GitLab in `push_data.action`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PushAction {
+    #[serde(rename = "pushed")]
+    Pushed,
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "removed")]
+    Removed,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RefType {
+    #[serde(rename = "branch")]
+    Branch,
+    #[serde(rename = "tag")]
+    Tag,
+}
+
+
+/// Filter on the action of the events.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum ListingAction {
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "updated")]
+    Updated,
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "reopened")]
+    Reopened,
+    #[serde(rename = "pushed")]
+    Pushed,
+    #[serde(rename = "commented")]
+    Commented,
+    #[serde(rename = "merged")]
+    Merged,
+    #[serde(rename = "joined")]
+    Joined,
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "destroyed")]
+    Destroyed,
+    #[serde(rename = "expired")]
+    Expired,
+}
+
+
+/// Filter on the type of the target of the events.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum ListingTargetType {
+    #[serde(rename = "issue")]
+    Issue,
+    #[serde(rename = "milestone")]
+    Milestone,
+    #[serde(rename = "merge_request")]
+    MergeRequest,
+    #[serde(rename = "note")]
+    Note,
+    #[serde(rename = "project")]
+    Project,
+    #[serde(rename = "snippet")]
+    Snippet,
+    #[serde(rename = "user")]
+    User,
+}
+
+
+/// Details of a push event (API v4 only).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PushData {
+    pub commit_count: i64,
+    pub action: PushAction,
+    pub ref_type: RefType,
+    pub commit_from: Option<String>,
+    pub commit_to: Option<String>,
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub commit_title: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub title: Option<String>,
+    pub project_id: Option<i64>,
+    pub action_name: EventAction,
+    pub target_id: Option<i64>,
+    pub target_iid: Option<i64>,
+    pub target_type: Option<TargetType>,
+    pub author_id: i64,
+    pub target_title: Option<String>,
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    pub author: Option<::User>,
+    pub author_username: Option<String>,
+    pub push_data: Option<PushData>,  // API v4 only
+    pub data: Option<serde_json::Value>,  // API v3 only: payload of the push hook
+    pub note: Option<::Note>,
+}
+
+pub type Events = Vec<Event>;
+
+
+impl EventAction {
+    fn from_name(name: &str) -> EventAction {
+        match name {
+            "created" => EventAction::Created,
+            "updated" => EventAction::Updated,
+            "opened" => EventAction::Opened,
+            "closed" => EventAction::Closed,
+            "reopened" => EventAction::Reopened,
+            "pushed to" => EventAction::PushedTo,
+            "pushed new" => EventAction::PushedNew,
+            "deleted" => EventAction::Deleted,
+            "commented on" => EventAction::CommentedOn,
+            "accepted" => EventAction::Accepted,
+            "joined" => EventAction::Joined,
+            "left" => EventAction::Left,
+            "destroyed" => EventAction::Destroyed,
+            "expired" => EventAction::Expired,
+            other => EventAction::Other(other.to_string()),
+        }
+    }
+
+    /// The name sent by GitLab.
+    pub fn name(&self) -> &str {
+        match *self {
+            EventAction::Created => "created",
+            EventAction::Updated => "updated",
+            EventAction::Opened => "opened",
+            EventAction::Closed => "closed",
+            EventAction::Reopened => "reopened",
+            EventAction::PushedTo => "pushed to",
+            EventAction::PushedNew => "pushed new",
+            EventAction::Deleted => "deleted",
+            EventAction::CommentedOn => "commented on",
+            EventAction::Accepted => "accepted",
+            EventAction::Joined => "joined",
+            EventAction::Left => "left",
+            EventAction::Destroyed => "destroyed",
+            EventAction::Expired => "expired",
+            EventAction::Other(ref name) => name,
+        }
+    }
+}
+
+
+impl TargetType {
+    fn from_name(name: &str) -> TargetType {
+        match name {
+            "Issue" => TargetType::Issue,
+            "MergeRequest" => TargetType::MergeRequest,
+            "Milestone" => TargetType::Milestone,
+            "Note" => TargetType::Note,
+            "DiffNote" => TargetType::DiffNote,
+            "DiscussionNote" => TargetType::DiscussionNote,
+            "Project" => TargetType::Project,
+            "Snippet" => TargetType::Snippet,
+            "User" => TargetType::User,
+            other => TargetType::Other(other.to_string()),
+        }
+    }
+
+    /// The name sent by GitLab.
+    pub fn name(&self) -> &str {
+        match *self {
+            TargetType::Issue => "Issue",
+            TargetType::MergeRequest => "MergeRequest",
+            TargetType::Milestone => "Milestone",
+            TargetType::Note => "Note",
+            TargetType::DiffNote => "DiffNote",
+            TargetType::DiscussionNote => "DiscussionNote",
+            TargetType::Project => "Project",
+            TargetType::Snippet => "Snippet",
+            TargetType::User => "User",
+            TargetType::Other(ref name) => name,
+        }
+    }
+}
+
+
+impl Serialize for EventAction {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+
+impl Deserialize for EventAction {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<EventAction, D::Error>
+        where D: Deserializer
+    {
+        String::deserialize(deserializer).map(|name| EventAction::from_name(&name))
+    }
+}
+
+
+impl Serialize for TargetType {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+
+impl Deserialize for TargetType {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<TargetType, D::Error>
+        where D: Deserializer
+    {
+        String::deserialize(deserializer).map(|name| TargetType::from_name(&name))
+    }
+}
+
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct EventsListerInternal {
+    /// Include only events of a particular action type
+    action: Option<ListingAction>,
+    /// Include only events of a particular target type
+    target_type: Option<ListingTargetType>,
+    /// Include only events created before a particular date
+    #[serde(default, with = "::dates::optional_date")]
+    before: Option<::dates::Date>,
+    /// Include only events created after a particular date
+    #[serde(default, with = "::dates::optional_date")]
+    after: Option<::dates::Date>,
+    /// Sort events by `created_at`. Default is `::ListingSort::Desc`.
+    sort: Option<::ListingSort>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct EventsLister<'a> {
+    gl: &'a ::GitLab,
+    internal: EventsListerInternal,
+}
+
+
+impl<'a> Lister<Events> for EventsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of events.
+    fn list(&self) -> Result<Events> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of events.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Events> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<Events>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> EventsLister<'a> {
+    pub fn new(gl: &'a ::GitLab) -> EventsLister {
+        EventsLister {
+            gl: gl,
+            internal: EventsListerInternal::default(),
+        }
+    }
+
+
+    pub fn action(&'a mut self, action: ListingAction) -> &'a mut EventsLister {
+        self.internal.action = Some(action);
+        self
+    }
+
+    pub fn target_type(&'a mut self, target_type: ListingTargetType) -> &'a mut EventsLister {
+        self.internal.target_type = Some(target_type);
+        self
+    }
+
+    /// Only events created before this date, sent in the format YEAR-MONTH-DAY (e.g.
+    /// `2017-03-11`).
+    pub fn before(&'a mut self, before: ::dates::Date) -> &'a mut EventsLister {
+        self.internal.before = Some(before);
+        self
+    }
+
+    /// Only events created after this date, sent in the format YEAR-MONTH-DAY (e.g.
+    /// `2017-03-11`).
+    pub fn after(&'a mut self, after: ::dates::Date) -> &'a mut EventsLister {
+        self.internal.after = Some(after);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut EventsLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+}
+
+
+impl<'a> BuildQuery for EventsLister<'a> {
+    fn build_query(&self) -> String {
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        ::join_query("events", &[&encoded])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use BuildQuery;
+    use events::*;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        assert_eq!(gl.events().build_query(), "events");
+    }
+
+
+    #[test]
+    fn build_query_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.events()
+            .action(ListingAction::Merged)
+            .target_type(ListingTargetType::MergeRequest)
+            .before("2017-03-01".parse().unwrap())
+            .after("2017-02-01".parse().unwrap())
+            .sort(::ListingSort::Asc)
+            .build_query();
+        assert_eq!(query,
+                   "events?action=merged&target_type=merge_request&before=2017-03-01&\
+                    after=2017-02-01&sort=asc");
+    }
+
+
+    #[test]
+    fn deserialize_events() {
+        let json_reply = r#"[
+            {
+                "title": null, "project_id": 15, "action_name": "pushed to",
+                "target_id": null, "target_iid": null, "target_type": null, "author_id": 1,
+                "target_title": null, "created_at": "2017-02-09T10:43:19.426Z",
+                "author": {"name": "Administrator", "username": "root", "id": 1,
+                           "state": "active", "avatar_url": null,
+                           "web_url": "http://localhost:3000/root"},
+                "author_username": "root",
+                "push_data": {"commit_count": 1, "action": "pushed", "ref_type": "branch",
+                              "commit_from": "50d4420237a9de7be1304607147aec22e4a14af7",
+                              "commit_to": "c5feabde2d8cd023215af4d2ceeb7a64839fc428",
+                              "ref": "master",
+                              "commit_title": "Add simple search to projects in public area"}
+            },
+            {
+                "title": null, "project_id": 15, "action_name": "commented on",
+                "target_id": 1312, "target_iid": 1312, "target_type": "Note", "author_id": 1,
+                "target_title": null, "created_at": "2015-12-04T10:33:58.089Z",
+                "note": {"id": 1312, "body": "What an awesome day!", "attachment": null,
+                         "author": {"name": "Administrator", "username": "root", "id": 1,
+                                    "state": "active", "avatar_url": null,
+                                    "web_url": "http://localhost:3000/root"},
+                         "created_at": "2015-12-04T10:33:56.698Z", "system": false,
+                         "noteable_id": 377, "noteable_type": "Issue"},
+                "author": {"name": "Administrator", "username": "root", "id": 1,
+                           "state": "active", "avatar_url": null,
+                           "web_url": "http://localhost:3000/root"},
+                "author_username": "root"
+            },
+            {
+                "title": null, "project_id": 15, "action_name": "starred",
+                "target_id": null, "target_type": "Epic", "author_id": 1,
+                "target_title": null, "created_at": "2017-02-09T10:43:19.426Z",
+                "data": {"before": "50d4420237a9de7be1304607147aec22e4a14af7"}
+            }
+        ]"#;
+
+        let events: Events = serde_json::from_str(json_reply).unwrap();
+
+        assert_eq!(events[0].action_name, EventAction::PushedTo);
+        let push_data = events[0].push_data.as_ref().unwrap();
+        assert_eq!(push_data.action, PushAction::Pushed);
+        assert_eq!(push_data.ref_type, RefType::Branch);
+        assert_eq!(push_data.ref_, "master");
+
+        assert_eq!(events[1].action_name, EventAction::CommentedOn);
+        assert_eq!(events[1].target_type, Some(TargetType::Note));
+        assert_eq!(events[1].note.as_ref().unwrap().body, "What an awesome day!");
+
+        assert_eq!(events[2].action_name, EventAction::Other(String::from("starred")));
+        assert_eq!(events[2].target_type, Some(TargetType::Other(String::from("Epic"))));
+        assert!(events[2].data.is_some());
+        assert!(events[2].author.is_none());
+    }
+
+
+    #[test]
+    fn serialize_names() {
+        assert_eq!(serde_json::to_string(&EventAction::PushedNew).unwrap(), r#""pushed new""#);
+        assert_eq!(serde_json::to_string(&EventAction::Other(String::from("x"))).unwrap(),
+                   r#""x""#);
+        assert_eq!(serde_json::to_string(&TargetType::MergeRequest).unwrap(),
+                   r#""MergeRequest""#);
+    }
+}
@@ -1,29 +1,137 @@
 
-use std::io::Read;  // Trait providing read_to_string()
 use std;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use url;
-use hyper;
 use serde;
 use serde_json;
-use regex;
 
 
 // use Groups;
+use BuildQuery;
 use Lister;
+use pagination::{Page, Pagination};
+use rate_limit::RateLimiter;
+use retry::{Clock, RetryPolicy, SystemClock};
+use transport::{HttpConfig, HttpTransport, HyperTransport, Method, Request, Response};
+use transport::{JOB_TOKEN_HEADER, PRIVATE_TOKEN_HEADER};
 
 use ::errors::*;
 
 
+/// Version of the API used by default.
 pub const API_VERSION: u16 = 3;
 
 
+/// Version of the GitLab API to talk to.
+///
+/// Some endpoints and fields differ between versions; listers build their queries for the
+/// version of the `GitLab` instance they come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// API v3 (GitLab 8.x).
+    V3,
+    /// API v4 (GitLab 9.0 and later).
+    V4,
+}
+
+
+impl ApiVersion {
+    /// Version number, as used in the URL prefix (`/api/vN/`).
+    pub fn number(&self) -> u16 {
+        match *self {
+            ApiVersion::V3 => 3,
+            ApiVersion::V4 => 4,
+        }
+    }
+}
+
+
+impl Default for ApiVersion {
+    fn default() -> ApiVersion {
+        ApiVersion::V3
+    }
+}
+
+
+
+
+/// How requests are authenticated against the GitLab server.
+#[derive(Clone, PartialEq)]
+pub enum Auth {
+    /// Personal private token (or personal access token), sent as a `PRIVATE-TOKEN` header.
+    PrivateToken(String),
+    /// OAuth2 access token, sent as an `Authorization: Bearer` header.
+    OAuthBearer(String),
+    /// CI job token (`CI_JOB_TOKEN`), sent as a `JOB-TOKEN` header.
+    JobToken(String),
+    /// No authentication: only public endpoints can be accessed.
+    None,
+}
+
+
+impl Auth {
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Auth::PrivateToken(ref token) => {
+                if token.len() != 20 {
+                    bail!(format!("private token should be a 20 characters string (not {})",
+                                  token.len()));
+                }
+            }
+            Auth::OAuthBearer(ref token) |
+            Auth::JobToken(ref token) => {
+                if token.is_empty() {
+                    bail!("token cannot be empty");
+                }
+            }
+            Auth::None => {}
+        }
+        Ok(())
+    }
+
+    /// Header (name and value) authenticating a request, if any.
+    fn header(&self) -> Option<(String, String)> {
+        match *self {
+            Auth::PrivateToken(ref token) => {
+                Some((String::from(PRIVATE_TOKEN_HEADER), token.clone()))
+            }
+            Auth::OAuthBearer(ref token) => {
+                Some((String::from("Authorization"), format!("Bearer {}", token)))
+            }
+            Auth::JobToken(ref token) => Some((String::from(JOB_TOKEN_HEADER), token.clone())),
+            Auth::None => None,
+        }
+    }
+}
+
+
+// Explicitly implement Debug trait for Auth so we can hide the token.
+impl std::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Auth::PrivateToken(_) => write!(f, "private_token: XXXXXXXXXXXXXXXXXXXX"),
+            Auth::OAuthBearer(_) => write!(f, "oauth_token: XXXXXXXXXXXXXXXXXXXX"),
+            Auth::JobToken(_) => write!(f, "job_token: XXXXXXXXXXXXXXXXXXXX"),
+            Auth::None => write!(f, "no authentication"),
+        }
+    }
+}
 
 
 pub struct GitLab {
     url: url::Url,
-    private_token: String,
-    client: hyper::Client,
+    api_version: ApiVersion,
+    auth: Auth,
+    http_config: HttpConfig,
+    /// Transport set with `transport()`, used instead of the default one.
+    transport: Option<Arc<HttpTransport>>,
+    /// Default `HyperTransport`, built from `http_config` when first needed.
+    default_transport: Mutex<Option<Arc<HttpTransport>>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    clock: Box<Clock>,
 }
 
 
@@ -31,13 +139,14 @@ pub struct GitLab {
 impl std::fmt::Debug for GitLab {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f,
-               "GitLab {{ scheme: {}, domain: {}, port: {}, private_token: XXXXXXXXXXXXXXXXXXXX }}",
+               "GitLab {{ scheme: {}, domain: {}, port: {}, {:?} }}",
                self.url.scheme(),
                self.url.domain().unwrap_or("bad hostname provided"),
                self.url
                    .port()
                    .map(|port_u16| port_u16.to_string())
-                   .unwrap_or("no port provided".to_string()))
+                   .unwrap_or("no port provided".to_string()),
+               self.auth)
     }
 }
 
@@ -58,7 +167,7 @@ fn validate_url(scheme: &str, domain: &str, port: u16) -> Result<url::Url> {
         bail!(format!("invalid domain: '{}' cannot end with a dot", domain));
     }
 
-    let url_string = format!("{}://{}/api/v{}/", scheme, domain, API_VERSION);
+    let url_string = format!("{}://{}/api/v{}/", scheme, domain, ApiVersion::default().number());
     let mut url = url::Url::parse(&url_string)
         .chain_err(|| format!("failure to parse URL '{}'", url_string))?;
     url.set_port(Some(port)).expect("bad port provided");
@@ -78,27 +187,27 @@ fn validate_url(scheme: &str, domain: &str, port: u16) -> Result<url::Url> {
 
 impl GitLab {
     pub fn _new(scheme: &str, domain: &str, port: u16, private_token: &str) -> Result<GitLab> {
-        if private_token.len() != 20 {
-            bail!(format!("private token should be a 20 characters string (not {})",
-                          private_token.len()));
-        }
+        GitLab::_new_with_auth(scheme,
+                               domain,
+                               port,
+                               Auth::PrivateToken(private_token.to_string()))
+    }
+
+    pub fn _new_with_auth(scheme: &str, domain: &str, port: u16, auth: Auth) -> Result<GitLab> {
+        auth.validate()?;
 
         let url: url::Url = validate_url(scheme, domain, port).chain_err(|| "invalid URL")?;
 
         Ok(GitLab {
             url: url,
-            private_token: private_token.to_string(),
-            client: match std::env::var("HTTP_PROXY") {
-                Ok(proxy) => {
-                    let proxy: Vec<&str> = proxy.trim_left_matches("http://").split(':').collect();
-                    let hostname = proxy[0].to_string();
-                    let port = proxy[1].parse()
-                        .chain_err(|| format!("failure to set port to {}", proxy[1]))?;
-
-                    hyper::Client::with_http_proxy(hostname, port)
-                }
-                Err(_) => hyper::Client::new(),
-            },
+            api_version: ApiVersion::default(),
+            auth: auth,
+            http_config: HttpConfig::default(),
+            transport: None,
+            default_transport: Mutex::new(None),
+            retry_policy: RetryPolicy::never(),
+            rate_limiter: None,
+            clock: Box::new(SystemClock),
         })
     }
 
@@ -111,6 +220,30 @@ impl GitLab {
         GitLab::_new("https", domain, 443, private_token)
     }
 
+    /// Like `new_insecure()`, but authenticating with `auth` instead of a private token.
+    pub fn new_insecure_with_auth(domain: &str, auth: Auth) -> Result<GitLab> {
+        warn!("Using insecure http:// protocol: Token will be sent in clear!");
+        GitLab::_new_with_auth("http", domain, 80, auth)
+    }
+
+    /// Like `new()`, but authenticating with `auth` instead of a private token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gitlab_api::{Auth, GitLab};
+    ///
+    /// // Inside a GitLab CI job
+    /// let token = std::env::var("CI_JOB_TOKEN").unwrap_or(String::from("job-token"));
+    /// let gl = GitLab::new_with_auth("gitlab.example.com", Auth::JobToken(token)).unwrap();
+    ///
+    /// // Public endpoints only
+    /// let gl = GitLab::new_with_auth("gitlab.example.com", Auth::None).unwrap();
+    /// ```
+    pub fn new_with_auth(domain: &str, auth: Auth) -> Result<GitLab> {
+        GitLab::_new_with_auth("https", domain, 443, auth)
+    }
+
     pub fn port(mut self, port: u16) -> Self {
         self.url.set_port(Some(port)).unwrap();
         self
@@ -121,8 +254,153 @@ impl GitLab {
         self
     }
 
+    /// Select the version of the API to use (`ApiVersion::V3` by default).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gitlab_api::{ApiVersion, GitLab};
+    ///
+    /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+    ///     .unwrap()
+    ///     .api_version(ApiVersion::V4);
+    ///
+    /// assert_eq!(gl.build_url("version").unwrap(), "https://gitlab.example.com/api/v4/version");
+    /// ```
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self {
+        self.url.set_path(&format!("/api/v{}/", api_version.number()));
+        self.api_version = api_version;
+        self
+    }
+
+    /// Version of the API in use.
+    pub fn get_api_version(&self) -> ApiVersion {
+        self.api_version
+    }
+
+    /// Replace the HTTP transport used to contact the server (`HyperTransport` by default).
+    ///
+    /// See `transport::MockTransport` for an in-memory transport useful in tests.
+    pub fn transport<T>(mut self, transport: T) -> Self
+        where T: HttpTransport + 'static
+    {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Maximum time to set up a connection to the server, TLS handshake included (no timeout by
+    /// default). Establishing the TCP connection itself is not bounded.
+    ///
+    /// The connection settings (`connect_timeout()`, `read_timeout()`, `write_timeout()` and
+    /// `max_idle_connections()`) apply to the default `HyperTransport`, built when the first
+    /// request is sent. They are kept but unused while a transport is set with `transport()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use gitlab_api::GitLab;
+    ///
+    /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+    ///     .unwrap()
+    ///     .connect_timeout(Duration::from_secs(10))
+    ///     .read_timeout(Duration::from_secs(30))
+    ///     .max_idle_connections(10);
+    ///
+    /// assert_eq!(gl.get_http_config().read_timeout, Some(Duration::from_secs(30)));
+    /// ```
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        self.configure_http(|config| config.connect_timeout = Some(timeout))
+    }
+
+    /// Maximum time to wait for data from the server (no timeout by default).
+    ///
+    /// See `connect_timeout()`.
+    pub fn read_timeout(self, timeout: Duration) -> Self {
+        self.configure_http(|config| config.read_timeout = Some(timeout))
+    }
+
+    /// Maximum time to wait for the server to accept data (no timeout by default).
+    ///
+    /// See `connect_timeout()`.
+    pub fn write_timeout(self, timeout: Duration) -> Self {
+        self.configure_http(|config| config.write_timeout = Some(timeout))
+    }
+
+    /// Maximum number of idle connections kept alive to be reused (5 by default). `0` disables
+    /// keep-alive.
+    ///
+    /// See `connect_timeout()`.
+    pub fn max_idle_connections(self, max_idle_connections: usize) -> Self {
+        self.configure_http(|config| config.max_idle_connections = max_idle_connections)
+    }
+
+    /// Settings of the connections to the server.
+    pub fn get_http_config(&self) -> &HttpConfig {
+        &self.http_config
+    }
+
+    fn configure_http<F>(mut self, configure: F) -> Self
+        where F: FnOnce(&mut HttpConfig)
+    {
+        configure(&mut self.http_config);
+        // Rebuild the default transport with the new settings on the next request.
+        self.default_transport = Mutex::new(None);
+        self
+    }
+
+    /// Transport to send requests with: the one set with `transport()` or else the default
+    /// `HyperTransport`, built on first use.
+    fn get_transport(&self) -> Result<Arc<HttpTransport>> {
+        if let Some(ref transport) = self.transport {
+            return Ok(transport.clone());
+        }
+
+        let mut default_transport = self.default_transport.lock().unwrap();
+        if let Some(ref transport) = *default_transport {
+            return Ok(transport.clone());
+        }
+        let transport: Arc<HttpTransport> =
+            Arc::new(HyperTransport::with_config(self.http_config.clone())
+                .chain_err(|| "cannot create HTTP transport")?);
+        *default_transport = Some(transport.clone());
+        Ok(transport)
+    }
+
+    /// Retry requests failing with a transient error according to `retry_policy` (never
+    /// retried by default).
+    ///
+    /// See the `retry` module.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Limit the rate of the requests sent (not limited by default).
+    ///
+    /// The limiter applies to all the requests sent through this `GitLab`, including retries,
+    /// whatever the lister or thread sending them. See the `rate_limit` module.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Replace the clock used to wait between retries and by the rate limiter (`SystemClock` by
+    /// default).
+    ///
+    /// See `retry::MockClock` for a clock which does not wait, useful in tests.
+    pub fn clock<C>(mut self, clock: C) -> Self
+        where C: Clock + 'static
+    {
+        self.clock = Box::new(clock);
+        self
+    }
+
     /// Build a URL used to access GitLab instance, including some parameters.
     ///
+    /// The URL never contains the token: it is sent in a header of each request instead (see
+    /// `Auth`).
+    ///
     /// # Examples
     ///
     /// Example from GitLab: https://docs.gitlab.com/ce/api/#basic-usage
@@ -131,24 +409,24 @@ impl GitLab {
     /// use gitlab_api::GitLab;
     ///
     /// let expected_url = "https://gitlab.example.com\
-    ///                     /api/v3/groups?order_by=path&private_token=XXXXXXXXXXXXXXXXXXXX";
+    ///                     /api/v3/groups?order_by=path";
     ///
     /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
     ///
     /// assert_eq!(gl.build_url("groups?order_by=path").unwrap(), expected_url);
     /// ```
     pub fn build_url(&self, query: &str) -> Result<String> {
-        let mut new_url = self.url
-            .clone()
+        Ok(self.join_url(query)?.into_string())
+    }
+
+    fn join_url(&self, query: &str) -> Result<url::Url> {
+        self.url
             .join(query)
             .chain_err(|| {
                 format!("Failure to join query '{}' to url {}",
                         query,
                         self.url.as_str())
-            })?;
-        new_url.query_pairs_mut().append_pair("private_token", &self.private_token);
-
-        Ok(new_url.into_string())
+            })
     }
 
     // pub fn attempt_connection(&self) -> Result<hyper::client::Response, hyper::Error> {
@@ -177,43 +455,186 @@ impl GitLab {
         where T: serde::Deserialize,
               U: Into<Option<u16>>
     {
-        let mut url = self.build_url(query)
+        let response = self.get_response(query, page, per_page)?;
+
+        deserialize(&response.body)
+    }
+
+    /// Perform an HTTP GET to the GitLab server from a specific query, keeping the pagination
+    /// information sent back by the server.
+    ///
+    /// See `get()` and the `pagination` module.
+    pub fn get_page<T, U>(&self, query: &str, page: U, per_page: U) -> Result<Page<T>>
+        where T: serde::Deserialize,
+              U: Into<Option<u16>>
+    {
+        let response = self.get_response(query, page, per_page)?;
+
+        Ok(Page {
+            items: deserialize(&response.body)?,
+            pagination: Pagination::from_response(&response),
+        })
+    }
+
+    /// Perform an HTTP POST to the GitLab server, sending `body` encoded as JSON.
+    ///
+    /// The `query` has the same meaning as for `get()`. Returns the item created, as sent back by
+    /// GitLab.
+    ///
+    /// This method is meant to be used internally.
+    pub fn post<T, B>(&self, query: &str, body: &B) -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        let response = self.send_with_body(Method::Post, query, body)?;
+
+        deserialize(&response.body)
+    }
+
+    /// Perform an HTTP PUT to the GitLab server, sending `body` encoded as JSON.
+    ///
+    /// The `query` has the same meaning as for `get()`. Returns the item updated, as sent back by
+    /// GitLab.
+    ///
+    /// This method is meant to be used internally.
+    pub fn put<T, B>(&self, query: &str, body: &B) -> Result<T>
+        where T: serde::Deserialize,
+              B: serde::Serialize
+    {
+        let response = self.send_with_body(Method::Put, query, body)?;
+
+        deserialize(&response.body)
+    }
+
+    /// Perform an HTTP DELETE to the GitLab server.
+    ///
+    /// The `query` has the same meaning as for `get()`. Whatever GitLab sends back is discarded.
+    ///
+    /// This method is meant to be used internally.
+    pub fn delete(&self, query: &str) -> Result<()> {
+        let url = self.build_url(query)
+            .chain_err(|| format!("failure to build url for query '{}'", query))?;
+
+        self.send(Request::new(Method::Delete, &url), query).map(|_| ())
+    }
+
+    /// Send the GET request for `get()` and `get_page()`.
+    fn get_response<U>(&self, query: &str, page: U, per_page: U) -> Result<Response>
+        where U: Into<Option<u16>>
+    {
+        let mut url = self.join_url(query)
             .chain_err(|| format!("failure to build url for query '{}'", query))?;
-        info!("url: {:?}", remove_gitlab_token_from_url(&url));
 
         // Add pagination information if requested.
-        page.into().map(|page| url.push_str(&format!("&page={}", page)));
-        per_page.into().map(|per_page| url.push_str(&format!("&per_page={}", per_page)));
-
-        // Close connections after each GET.
-        let mut res: hyper::client::Response = self.client
-            .get(&url)
-            .header(hyper::header::Connection::close())
-            .send()
-            .chain_err(|| format!("cannot send request '{}' to {:?}", query, self))?;
-        info!("res.status: {:?}", res.status);
-        // The headers might leak the token, don't print them.
-        // debug!("res.headers: {:?}", res.headers);
-        // Hide the url's token in logger
-        debug!("res.url: {}",
-               remove_gitlab_token_from_url(res.url.as_str()));
+        if let Some(page) = page.into() {
+            url.query_pairs_mut().append_pair("page", &page.to_string());
+        }
+        if let Some(per_page) = per_page.into() {
+            url.query_pairs_mut().append_pair("per_page", &per_page.to_string());
+        }
 
-        let mut body = String::new();
-        res.read_to_string(&mut body).chain_err(|| "cannot read response body")?;
-        debug!("body:\n{}", body);
+        self.send(Request::new(Method::Get, url.as_str()), query)
+    }
+
+    /// Send the request for `post()` and `put()`.
+    fn send_with_body<B>(&self, method: Method, query: &str, body: &B) -> Result<Response>
+        where B: serde::Serialize
+    {
+        let url = self.build_url(query)
+            .chain_err(|| format!("failure to build url for query '{}'", query))?;
+
+        let mut request = Request::new(method, &url);
+        request.headers.push((String::from("Content-Type"), String::from("application/json")));
+        request.body = Some(serde_json::to_string(body)
+            .chain_err(|| format!("cannot serialize body of query '{}'", query))?);
+        debug!("request body:\n{}", request.body.as_ref().unwrap());
+
+        self.send(request, query)
+    }
+
+    /// Authenticate and send a request to the GitLab server, failing on a non-success status
+    /// code.
+    fn send(&self, mut request: Request, query: &str) -> Result<Response> {
+        info!("{} url: {:?}", request.method, request.url);
+
+        if let Some(header) = self.auth.header() {
+            request.headers.push(header);
+        }
+
+        let transport = self.get_transport()?;
+        let mut attempt = 1;
+        let mut result;
+        loop {
+            if let Some(ref rate_limiter) = self.rate_limiter {
+                rate_limiter.acquire(&*self.clock);
+            }
+            result = transport.send(&request);
+            if let (Some(rate_limiter), &Ok(ref response)) = (self.rate_limiter.as_ref(), &result) {
+                rate_limiter.observe(response, self.clock.now());
+            }
+
+            let delay = match result {
+                Ok(ref response) if response.status >= 200 && response.status < 300 => None,
+                Ok(ref response) => {
+                    let now = self.clock.now();
+                    self.retry_policy.retry_delay(request.method, attempt, Some(response), now)
+                }
+                Err(_) => {
+                    let now = self.clock.now();
+                    self.retry_policy.retry_delay(request.method, attempt, None, now)
+                }
+            };
+            let delay = match delay {
+                Some(delay) => delay,
+                None => break,
+            };
+
+            match result {
+                Ok(ref response) => {
+                    warn!("{} url: {:?}: status {}, retrying in {:?} (attempt {})",
+                          request.method,
+                          request.url,
+                          response.status,
+                          delay,
+                          attempt + 1)
+                }
+                Err(ref e) => {
+                    warn!("{} url: {:?}: {}, retrying in {:?} (attempt {})",
+                          request.method,
+                          request.url,
+                          e,
+                          delay,
+                          attempt + 1)
+                }
+            }
+            self.clock.sleep(delay);
+            attempt += 1;
+        }
+        let response = result.chain_err(|| {
+                ErrorKind::Transport(format!("cannot send request '{}' to {:?}", query, self))
+            })?;
+        info!("res.status: {:?}", response.status);
+        // The headers might leak the token, don't print them.
+        // debug!("res.headers: {:?}", response.headers);
+        debug!("body:\n{}", response.body);
 
-        if res.status != hyper::status::StatusCode::Ok {
-            bail!(format!("status code '{}', not '200 OK'", res.status));
+        if response.status < 200 || response.status >= 300 {
+            bail!(status_error_kind(&response));
         }
 
-        serde_json::from_str(body.as_str())
-            .chain_err(|| format!("cannot build Rust struct from JSON data: {}", body))
+        Ok(response)
     }
 
     pub fn version(&self) -> Result<::Version> {
         self.get("version", None, None).chain_err(|| "cannot query 'version'")
     }
 
+    /// Events of the authenticated user (API v4 only: listing them fails with `404 Not Found`
+    /// with API v3).
+    pub fn events(&self) -> ::events::EventsLister {
+        ::events::EventsLister::new(self)
+    }
+
     pub fn groups(&self) -> ::groups::GroupsLister {
         ::groups::GroupsLister::new(self)
     }
@@ -250,64 +671,44 @@ impl GitLab {
     /// until the proper item is found and returned.
     fn get_paginated_from_project<T, F, G, L>(&self, item_search_closure: F, iter_find_closure: G) -> Result<T>
         where F: Fn() -> L,
-              G: Fn(&<std::vec::IntoIter<T> as IntoIterator>::Item) -> bool,
+              G: Fn(&T) -> bool,
               L: Lister<Vec<T>>
     {
-        // Explicitly set the pagination information so we can iterate over the pages.
-        let mut pagination_page = 1;
-        let pagination_per_page = 20;
-
-        let mut found: Option<T>;
-
-        // Query GitLab inside the page loop
-        loop {
-            // Query GitLab, specifying the pagination information. Use a closure, passed as
-            // argument, to make this operation generic.
-            // To list project's issues:
-            // let found_items = self.issues().project(id).list_paginated(...).chain_err(...)?;
-            // To list project's merge requests:
-            // let found_items = self.merge_requests(id).list_paginated(...).chain_err(..)?;
-            // To get matching projects:
-            // let found_items = self.projects().search(name).list_paginated(...).chain_err(...)?;
-
-            let found_items =
-                item_search_closure().list_paginated(pagination_page, pagination_per_page)
-                    .chain_err(|| "cannot get item in GitLab::get_paginated_from_project()")?;
-
-            let nb_found = found_items.len();
-
-            // Find the right item in the vector, if any. Use the second closure passed as argument
-            // as the closure used in `find()`.
-            found = found_items.into_iter().find(&iter_find_closure);
-
-            // Break if we find something.
-            if found.is_some() {
-                break;
-            }
-
-            // Also break if the number of found items is less than the maximum allowed per page.
-            // In that case, there is no more match and we need to stop the loop (we did not find
-            // anything).
-            if nb_found < pagination_per_page as usize {
-                break;
+        // Query GitLab, using a closure passed as argument to make this operation generic.
+        // To list project's issues:
+        // let lister = self.issues().project(id);
+        // To list project's merge requests:
+        // let lister = self.merge_requests(id);
+        // To get matching projects:
+        // let lister = self.projects().search(name);
+        let lister = item_search_closure();
+
+        // Pages are fetched lazily by the iterator: stop querying GitLab as soon as the right
+        // item is found, using the second closure passed as argument.
+        for item in lister.iter() {
+            let item = item.chain_err(|| "cannot get item in GitLab::get_paginated_from_project()")?;
+            if iter_find_closure(&item) {
+                return Ok(item);
             }
-
-            // Bump to the next page
-            pagination_page += 1;
         }
 
-        // Return the found item
-        match found {
-            None => bail!("not found!"),
-            Some(item) => Ok(item),
-        }
+        bail!("not found!")
     }
 
     /// Get a specific "namespace/name" project.
-    /// NOTE: We can't search for "namespace/name", so we search for "name", and refine the match
-    ///       on the namespace. This means the operation could be slow as multiple query to the
-    ///       GitLab server might be required to find the right item.
+    ///
+    /// The project is fetched directly (`GET /projects/namespace%2Fname`). If GitLab does not
+    /// find it that way (as old servers do), we fall back to searching for "name" and refining
+    /// the match on the namespace, which could require multiple queries to the GitLab server.
     pub fn get_project(&self, namespace: &str, name: &str) -> Result<::projects::Project> {
+        let path = ::projects::ListingId::NamespaceProject(format!("{}/{}", namespace, name));
+        let query = self.projects().id(path).build_query();
+        match self.get(&query, None, None) {
+            Err(ref e) if e.kind().status() == Some(404) => {
+                debug!("project '{}/{}' not found directly, searching for it", namespace, name);
+            }
+            result => return result.chain_err(|| format!("cannot get query {}", query)),
+        }
 
         // Closure to search for the item, possibly returning multiple match on multiple pages.
         let query_gitlab_closure = || self.projects().search(name.to_string());
@@ -321,21 +722,30 @@ impl GitLab {
 
     /// Get a project issue from a its project's `namespace` and `name` and the issue's `iid`.
     ///
-    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, we will need to list issues
-    /// (grouped by pages of 20) until we find the proper issue matching the `id` requested.
+    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, the project's issues are
+    /// filtered on the `iid` requested (`GET /projects/ID/issues?iid=IID`). Old servers ignoring
+    /// this filter send all the issues instead: we then list them (grouped by pages of 20) until
+    /// we find the proper issue.
     ///
     /// **Note**: A `iid` is the issue number as seen by normal user, for example appearing on
     /// a GitLab URL. This `iid` can be used to reference an issue (in other issues, in commit
     /// messages, etc.) by prepending a pound sign to it, for example `#3`. An `id`, instead, is
     /// GitLab's internal and unique id associated with the issue.
-    ///
-    /// Because we need to search (and thus query the GitLab server possibly multiple times), this
-    /// _can_ be a slow operation if there is many issues in the project.
     pub fn get_issue(&self, namespace: &str, name: &str, iid: i64) -> Result<::issues::Issue> {
         // We first need to find the specific project.
         let project = self.get_project(namespace, name)
             .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
 
+        let issues = self.issues().project(project.id).iid(iid).list()
+            .chain_err(|| format!("cannot get issue #{} of '{}/{}'", iid, namespace, name))?;
+        if issues.is_empty() {
+            bail!(ErrorKind::NotFound(format!("issue #{} of '{}/{}'", iid, namespace, name)));
+        }
+        if let Some(issue) = issues.into_iter().find(|issue| issue.iid == iid) {
+            return Ok(issue);
+        }
+        debug!("filter on iid ignored by the server, listing all issues");
+
         // Closure to search for the item, possibly returning multiple match on multiple pages.
         let query_gitlab_closure = || self.issues().project(project.id);
         // Closure to find the right item in the found list on the page.
@@ -345,19 +755,11 @@ impl GitLab {
     }
 
     /// Get a project merge request from a its project's `namespace` and `name` and
-    /// the issue's `iid`.
-    ///
-    /// Since GitLab uses unique `id`s in its API and _not_ `iid`s, we will need to
-    /// list issues (grouped by pages of 20) until we find the proper issue matching
-    /// the `id` requested.
-    ///
-    /// **Note**: A `iid` is the issue number as seen by normal user, for example appearing on
-    /// a GitLab URL. This `iid` can be used to reference an issue (in other issues, in commit
-    /// messages, etc.) by prepending a pound sign to it, for example `#3`. An `id`, instead, is
-    /// GitLab's internal and unique id associated with the issue.
+    /// the merge request's `iid`.
     ///
-    /// Because we need to search (and thus query the GitLab server possibly multiple times), this
-    /// _can_ be a slow operation if there is many issues in the project.
+    /// As for `get_issue()`, the project's merge requests are filtered on the `iid` requested
+    /// (`GET /projects/ID/merge_requests?iid=IID`), falling back to listing them all on old
+    /// servers ignoring the filter.
     pub fn get_merge_request(&self,
                              namespace: &str,
                              name: &str,
@@ -368,6 +770,24 @@ impl GitLab {
         let project = self.get_project(namespace, name)
             .chain_err(|| format!("cannot get project '{}/{}'", namespace, name))?;
 
+        let merge_requests = self.merge_requests(project.id)
+            .iid(vec![iid])
+            .list()
+            .chain_err(|| {
+                format!("cannot get merge request !{} of '{}/{}'", iid, namespace, name)
+            })?;
+        if merge_requests.is_empty() {
+            bail!(ErrorKind::NotFound(format!("merge request !{} of '{}/{}'",
+                                              iid,
+                                              namespace,
+                                              name)));
+        }
+        let found = merge_requests.into_iter().find(|merge_request| merge_request.iid == iid);
+        if let Some(merge_request) = found {
+            return Ok(merge_request);
+        }
+        debug!("filter on iid ignored by the server, listing all merge requests");
+
         // Closure to search for the item, possibly returning multiple match on multiple pages.
         let query_gitlab_closure = || self.merge_requests(project.id);
         // Closure to find the right item in the found list on the page.
@@ -375,20 +795,134 @@ impl GitLab {
 
         self.get_paginated_from_project(query_gitlab_closure, iter_find_closure)
     }
+
+    /// Fetch the project, issue or merge request a web URL or reference points to.
+    ///
+    /// See the `references` module for the syntax understood. References without a project
+    /// (`#42`), to milestones, labels or commits cannot be resolved, nor web URLs pointing to
+    /// another GitLab instance than this one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gitlab_api::GitLab;
+    /// use gitlab_api::references::Resolved;
+    ///
+    /// let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+    ///
+    /// match gl.resolve("https://gitlab.example.com/group/project/issues/42").unwrap() {
+    ///     Resolved::Issue(issue) => println!("{}", issue.title),
+    ///     resolved => println!("not an issue: {:?}", resolved),
+    /// }
+    /// ```
+    pub fn resolve(&self, reference: &str) -> Result<::references::Resolved> {
+        use references::{Reference, Resolved};
+
+        let parsed = Reference::parse(reference)?;
+        if let Some(host) = parsed.host() {
+            let own_host = ::host_and_port(&self.url).unwrap_or_else(String::new);
+            if host != own_host {
+                bail!("cannot resolve '{}': it points to {}, not to {}", reference, host, own_host);
+            }
+        }
+        let parsed = match parsed {
+            Reference::Url { reference, .. } => *reference,
+            parsed => parsed,
+        };
+        let (namespace, name) = match parsed.project().and_then(|path| split_project_path(path)) {
+            Some(path) => path,
+            None => bail!("cannot resolve '{}': the project is unknown", reference),
+        };
+
+        match parsed {
+            Reference::Project(_) => {
+                let project = self.get_project(namespace, name)?;
+                Ok(Resolved::Project(Box::new(project)))
+            }
+            Reference::Issue { iid, .. } => {
+                self.get_issue(namespace, name, iid).map(Resolved::Issue)
+            }
+            Reference::MergeRequest { iid, .. } => {
+                self.get_merge_request(namespace, name, iid).map(Resolved::MergeRequest)
+            }
+            Reference::Url { .. } |
+            Reference::Milestone { .. } |
+            Reference::Label { .. } |
+            Reference::Commit { .. } => {
+                bail!("cannot resolve '{}': only projects, issues and merge requests can be",
+                      reference)
+            }
+        }
+    }
+}
+
+/// Split the full path of a project into its namespace and its name.
+fn split_project_path(path: &str) -> Option<(&str, &str)> {
+    path.rfind('/').map(|slash| (&path[..slash], &path[slash + 1..]))
+}
+
+fn deserialize<T>(body: &str) -> Result<T>
+    where T: serde::Deserialize
+{
+    serde_json::from_str(body).chain_err(|| ErrorKind::Deserialization(excerpt(body)))
+}
+
+/// Maximum number of characters of a response body kept in errors.
+const EXCERPT_LENGTH: usize = 200;
+
+/// The beginning of `body`, to be kept in an error.
+fn excerpt(body: &str) -> String {
+    match body.char_indices().nth(EXCERPT_LENGTH) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => body.to_string(),
+    }
 }
 
-/// Remove the private token from a URL string, replacing it with `${GITLAB_TOKEN}`.
-/// This allows setting the environment variable `${GITLAB_TOKEN}` and still be able
-/// to copy-paste a printed URL.
-fn remove_gitlab_token_from_url(url: &str) -> String {
-    let re = regex::Regex::new(r"private_token=\w{20}").unwrap();
-    re.replace_all(url, "private_token=$${GITLAB_TOKEN}").into()
+/// Extract the reason of a failure from the JSON body sent by GitLab.
+///
+/// GitLab sends either `{"message": ...}` or `{"error": ...}`; the `message` can also be an object
+/// mapping attributes to validation errors, which is kept as JSON. Fall back to an excerpt of the
+/// body when it is not JSON.
+fn error_message(body: &str) -> String {
+    let value: serde_json::Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return excerpt(body),
+    };
+    let field = value.as_object().and_then(|o| o.get("message").or_else(|| o.get("error")));
+    match field {
+        Some(field) => {
+            match field.as_str() {
+                Some(message) => message.to_string(),
+                None => serde_json::to_string(field).unwrap_or_else(|_| excerpt(body)),
+            }
+        }
+        None => excerpt(body),
+    }
+}
+
+/// Build the error kind matching the non-success status code of `response`.
+fn status_error_kind(response: &Response) -> ErrorKind {
+    let message = error_message(&response.body);
+    match response.status {
+        401 => ErrorKind::Unauthorized(message),
+        403 => ErrorKind::Forbidden(message),
+        404 => ErrorKind::NotFound(message),
+        409 => ErrorKind::Conflict(message),
+        429 => {
+            let retry_after = response.header("Retry-After").and_then(|v| v.trim().parse().ok());
+            ErrorKind::RateLimited(retry_after, message)
+        }
+        status if status >= 500 => ErrorKind::ServerError(status, message),
+        status => ErrorKind::HttpStatus(status, message),
+    }
 }
 
 
+
 #[cfg(test)]
 mod tests {
     use std::fmt;
+    use fixtures::project_json;
     use gitlab::*;
 
     fn verify_ok<T>(result: &Result<T>) {
@@ -590,12 +1124,587 @@ mod tests {
         verify_err(&gl);
     }
 
+    #[test]
+    fn new_with_auth() {
+        // Only private tokens have a fixed length.
+        let token = String::from("an-oauth2-access-token-longer-than-20-characters");
+        verify_ok(&GitLab::new_with_auth("gitlab.com", Auth::OAuthBearer(token)));
+        verify_ok(&GitLab::new_with_auth("gitlab.com", Auth::JobToken(String::from("short"))));
+        verify_ok(&GitLab::new_with_auth("gitlab.com", Auth::None));
+
+        verify_err(&GitLab::new_with_auth("gitlab.com", Auth::PrivateToken(String::from("X"))));
+        verify_err(&GitLab::new_with_auth("gitlab.com", Auth::OAuthBearer(String::new())));
+        verify_err(&GitLab::new_with_auth("gitlab.com", Auth::JobToken(String::new())));
+    }
+
+    #[test]
+    fn auth_headers_through_transport() {
+        use transport::{MockTransport, Response};
+
+        let version = r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#;
+        let auths = vec![(Auth::OAuthBearer(String::from("oauth-secret")),
+                          Some(("Authorization", "Bearer oauth-secret"))),
+                         (Auth::JobToken(String::from("job-secret")),
+                          Some(("JOB-TOKEN", "job-secret"))),
+                         (Auth::None, None)];
+
+        for (auth, expected) in auths {
+            let mock = MockTransport::new();
+            mock.push_response(Response::new(200, version));
+            let gl = GitLab::new_with_auth("gitlab.example.com", auth)
+                .unwrap()
+                .transport(mock.clone());
+            assert!(!format!("{:?}", gl).contains("secret"));
+
+            verify_ok(&gl.version());
+
+            let request = &mock.requests()[0];
+            assert!(!format!("{:?}", request).contains("secret"));
+            assert_eq!(request.header("PRIVATE-TOKEN"), None);
+            match expected {
+                Some((name, value)) => {
+                    assert_eq!(request.headers.len(), 1);
+                    assert_eq!(request.header(name), Some(value));
+                }
+                None => assert!(request.headers.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn get_through_transport() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#));
+        mock.push_response(Response::new(404, r#"{"message": "404 Not Found"}"#));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let version = gl.version();
+        verify_ok(&version);
+        let version = version.unwrap();
+        assert_eq!(version.version, "8.16.4");
+        assert_eq!(version.revision, "b7e4c2d");
+
+        verify_err(&gl.version());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(requests[0].url, "https://gitlab.example.com/api/v3/version");
+        assert_eq!(requests[0].header("PRIVATE-TOKEN"), Some("XXXXXXXXXXXXXXXXXXXX"));
+    }
+
+    #[test]
+    fn http_settings_keep_transport() {
+        use std::time::Duration;
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone())
+            .connect_timeout(Duration::from_secs(10))
+            .read_timeout(Duration::from_secs(30))
+            .write_timeout(Duration::from_secs(30))
+            .max_idle_connections(0);
+        assert_eq!(gl.get_http_config().read_timeout, Some(Duration::from_secs(30)));
+
+        verify_ok(&gl.version());
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn token_not_in_url_nor_debug() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, "[]"));
+
+        let gl = GitLab::new("gitlab.example.com", "0123456789abcdefghij")
+            .unwrap()
+            .transport(mock.clone());
+        let groups: ::Groups = gl.get("groups?owned=true", 2, 50).unwrap();
+        assert!(groups.is_empty());
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/groups?owned=true&page=2&per_page=50");
+        assert_eq!(requests[0].header("PRIVATE-TOKEN"), Some("0123456789abcdefghij"));
+        assert!(!format!("{:?}", requests[0]).contains("0123456789abcdefghij"));
+        assert!(!format!("{:?}", gl).contains("0123456789abcdefghij"));
+    }
+
+    #[test]
+    fn post_put_delete_through_transport() {
+        use transport::{MockTransport, Response};
+
+        let issue = r#"{"id": 1, "iid": 1, "project_id": 3, "title": "Title",
+            "description": "", "state": "opened", "created_at": "2017-02-28T14:32:00.000Z",
+            "updated_at": "2017-02-28T14:32:00.000Z", "labels": [], "milestone": null,
+            "assignee": null, "author": {"name": "Name", "username": "username", "id": 1,
+            "state": "active", "avatar_url": null, "web_url": null}, "subscribed": true,
+            "user_notes_count": 0, "upvotes": 0, "downvotes": 0, "due_date": null,
+            "confidential": false, "web_url": null}"#;
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(201, issue));
+        mock.push_response(Response::new(200, issue));
+        mock.push_response(Response::new(200, ""));
+        mock.push_response(Response::new(403, r#"{"message": "403 Forbidden"}"#));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let created = gl.issues().create(3, String::from("Title")).create();
+        verify_ok(&created);
+        assert_eq!(created.unwrap().title, "Title");
+
+        let edited = gl.issues().edit(3, 1).state_event(::issues::StateEvent::Close).edit();
+        verify_ok(&edited);
+
+        verify_ok(&gl.issues().delete(3, 1).delete());
+        verify_err(&gl.issues().delete(3, 1).delete());
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, Method::Post);
+        assert!(requests[0].url
+            .starts_with("https://gitlab.example.com/api/v3/projects/3/issues"));
+        assert_eq!(requests[0].header("Content-Type"), Some("application/json"));
+        assert_eq!(requests[0].body, Some(String::from(r#"{"title":"Title"}"#)));
+        assert_eq!(requests[1].method, Method::Put);
+        assert!(requests[1].url
+            .starts_with("https://gitlab.example.com/api/v3/projects/3/issues/1"));
+        assert_eq!(requests[1].body, Some(String::from(r#"{"state_event":"close"}"#)));
+        assert_eq!(requests[2].method, Method::Delete);
+        assert_eq!(requests[2].body, None);
+    }
+
+    #[test]
+    fn status_errors_through_transport() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(401, r#"{"message": "401 Unauthorized"}"#));
+        mock.push_response(Response::new(403, r#"{"message": "403 Forbidden"}"#));
+        mock.push_response(Response::new(404, r#"{"message": "404 Project Not Found"}"#));
+        mock.push_response(Response::new(409, r#"{"message": "Branch already exists"}"#));
+        mock.push_response(Response::new(429, "Retry later\n").with_header("Retry-After", "30"));
+        mock.push_response(Response::new(502, "<html>Bad Gateway</html>"));
+        mock.push_response(Response::new(400, r#"{"error": "title is missing"}"#));
+        mock.push_response(Response::new(200, "not JSON"));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock);
+
+        let error = || gl.get::<::Version, _>("version", None, None).unwrap_err();
+
+        match *error().kind() {
+            ErrorKind::Unauthorized(ref message) => assert_eq!(message, "401 Unauthorized"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::Forbidden(ref message) => assert_eq!(message, "403 Forbidden"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::NotFound(ref message) => assert_eq!(message, "404 Project Not Found"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::Conflict(ref message) => assert_eq!(message, "Branch already exists"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::RateLimited(retry_after, ref message) => {
+                assert_eq!(retry_after, Some(30));
+                assert_eq!(message, "Retry later\n");
+            }
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::ServerError(status, ref message) => {
+                assert_eq!(status, 502);
+                assert_eq!(message, "<html>Bad Gateway</html>");
+            }
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::HttpStatus(status, ref message) => {
+                assert_eq!(status, 400);
+                assert_eq!(message, "title is missing");
+            }
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        match *error().kind() {
+            ErrorKind::Deserialization(ref excerpt) => assert_eq!(excerpt, "not JSON"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        // No response left: the mock transport fails.
+        match *error().kind() {
+            ErrorKind::Transport(_) => {}
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn error_kind_status() {
+        assert_eq!(ErrorKind::NotFound(String::new()).status(), Some(404));
+        assert_eq!(ErrorKind::RateLimited(None, String::new()).status(), Some(429));
+        assert_eq!(ErrorKind::ServerError(503, String::new()).status(), Some(503));
+        assert_eq!(ErrorKind::Transport(String::new()).status(), None);
+    }
+
+    #[test]
+    fn error_message_from_body() {
+        assert_eq!(error_message(r#"{"message": "404 Not found"}"#), "404 Not found");
+        assert_eq!(error_message(r#"{"error": "invalid_token"}"#), "invalid_token");
+        assert_eq!(error_message(r#"{"message": {"title": ["can't be blank"]}}"#),
+                   r#"{"title":["can't be blank"]}"#);
+        assert_eq!(error_message("<html></html>"), "<html></html>");
+
+        let long: String = std::iter::repeat('x').take(EXCERPT_LENGTH + 1).collect();
+        assert_eq!(error_message(&long), format!("{}...", &long[..EXCERPT_LENGTH]));
+    }
+
+    #[test]
+    fn merge_request_errors_through_transport() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        for status in &[405, 406, 409, 405, 406, 409, 404] {
+            mock.push_response(Response::new(*status, r#"{"message": "error"}"#));
+        }
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let kind = |result: Result<::merge_requests::MergeRequest>| {
+            match *result.unwrap_err().kind() {
+                ErrorKind::MergeRequestNotMergeable => "not mergeable",
+                ErrorKind::MergeRequestNotOpen => "not open",
+                ErrorKind::MergeRequestShaMismatch => "sha mismatch",
+                ErrorKind::MergeRequestNotMergingWhenBuildSucceeds => "not merging",
+                ErrorKind::MergeRequestAlreadyExists => "already exists",
+                _ => "other",
+            }
+        };
+
+        assert_eq!(kind(gl.merge_requests(3).accept(1).accept()), "not mergeable");
+        assert_eq!(kind(gl.merge_requests(3).accept(1).accept()), "not open");
+        assert_eq!(kind(gl.merge_requests(3).accept(1).accept()), "sha mismatch");
+        assert_eq!(kind(gl.merge_requests(3).cancel_merge_when_build_succeeds(1).cancel()),
+                   "not open");
+        assert_eq!(kind(gl.merge_requests(3).cancel_merge_when_build_succeeds(1).cancel()),
+                   "not merging");
+        let create = |gl: &GitLab| {
+            gl.merge_requests(3)
+                .create(String::from("feature"), String::from("master"), String::from("Title"))
+                .create()
+        };
+        assert_eq!(kind(create(&gl)), "already exists");
+        // Statuses without a specific meaning are not mapped.
+        assert_eq!(kind(create(&gl)), "other");
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, Method::Put);
+        assert!(requests[0].url
+            .starts_with("https://gitlab.example.com/api/v3/projects/3/merge_requests/1/merge"));
+        assert_eq!(requests[3].method, Method::Post);
+        assert!(requests[3].url.starts_with("https://gitlab.example.com/api/v3/projects/3/\
+                                             merge_requests/1/cancel_merge_when_build_succeeds"));
+        assert_eq!(requests[3].body, Some(String::from("{}")));
+    }
+
     #[test]
     fn build_url_doc() {
         let expected_url = "https://gitlab.example.com\
-                            /api/v3/groups?order_by=path&private_token=XXXXXXXXXXXXXXXXXXXX";
+                            /api/v3/groups?order_by=path";
         let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
         let url = gl.build_url("groups?order_by=path").unwrap();
         assert_eq!(url, expected_url);
     }
+
+
+    #[test]
+    fn retry_transient_errors() {
+        use std::time::Duration;
+        use retry::{MockClock, RetryPolicy};
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(503, "<html>Service Unavailable</html>"));
+        mock.push_response(Response::new(429, "Retry later\n").with_header("Retry-After", "7"));
+        mock.push_response(Response::new(200, r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#));
+        let clock = MockClock::default();
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone())
+            .clock(clock.clone())
+            .retry_policy(RetryPolicy::new().jitter(0.0));
+
+        assert_eq!(gl.version().unwrap().version, "8.16.4");
+        assert_eq!(mock.requests().len(), 3);
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(1), Duration::from_secs(7)]);
+    }
+
+
+    #[test]
+    fn retry_gives_up() {
+        use retry::{MockClock, RetryPolicy};
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        for _ in 0..3 {
+            mock.push_response(Response::new(429, "Retry later\n").with_header("Retry-After", "1"));
+        }
+        mock.push_response(Response::new(503, ""));
+        let clock = MockClock::default();
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone())
+            .clock(clock.clone())
+            .retry_policy(RetryPolicy::new().max_attempts(3));
+
+        match *gl.get::<::Version, _>("version", None, None).unwrap_err().kind() {
+            ErrorKind::RateLimited(retry_after, _) => assert_eq!(retry_after, Some(1)),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        assert_eq!(mock.requests().len(), 3);
+        assert_eq!(clock.sleeps().len(), 2);
+
+        // Not idempotent, not retried
+        let error = gl.post::<::Version, _>("version", &()).unwrap_err();
+        assert_eq!(error.kind().status(), Some(503));
+        assert_eq!(mock.requests().len(), 4);
+        assert_eq!(clock.sleeps().len(), 2);
+    }
+
+
+    #[test]
+    fn no_retry_by_default() {
+        use retry::MockClock;
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(503, ""));
+        let clock = MockClock::default();
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone())
+            .clock(clock.clone());
+
+        assert!(gl.version().is_err());
+        assert_eq!(mock.requests().len(), 1);
+        assert!(clock.sleeps().is_empty());
+    }
+
+
+    #[test]
+    fn rate_limited_requests() {
+        use std::time::{Duration, UNIX_EPOCH};
+        use rate_limit::RateLimiter;
+        use retry::MockClock;
+        use transport::{MockTransport, Response};
+
+        let version = r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#;
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, version));
+        mock.push_response(Response::new(200, version)
+            .with_header("RateLimit-Remaining", "1")
+            .with_header("RateLimit-Reset", "1488292380"));
+        mock.push_response(Response::new(200, version));
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_488_292_320));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone())
+            .clock(clock.clone())
+            .rate_limiter(RateLimiter::new(4.0, 2));
+
+        for _ in 0..3 {
+            gl.version().unwrap();
+        }
+        // Within the burst size, but slowed down by GitLab's limit.
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(30)]);
+    }
+
+
+    fn issue_json(iid: i64) -> String {
+        format!(r#"{{"id": {iid}0, "iid": {iid}, "project_id": 3, "title": "Title",
+            "description": "", "state": "opened", "created_at": "2017-02-28T14:32:00.000Z",
+            "updated_at": "2017-02-28T14:32:00.000Z", "labels": [], "milestone": null,
+            "assignee": null, "author": {{"name": "Name", "username": "username", "id": 1,
+            "state": "active", "avatar_url": null, "web_url": null}},
+            "user_notes_count": 0, "upvotes": 0, "downvotes": 0, "due_date": null,
+            "confidential": false, "web_url": null}}"#,
+                iid = iid)
+    }
+
+    fn merge_request_json(iid: i64) -> String {
+        format!(r#"{{"id": {iid}0, "iid": {iid}, "project_id": 3, "title": "Title",
+            "description": "", "state": "opened", "created_at": "2017-02-28T14:32:00.000Z",
+            "updated_at": "2017-02-28T14:32:00.000Z", "target_branch": "master",
+            "source_branch": "feature", "upvotes": 0, "downvotes": 0,
+            "author": {{"name": "Name", "username": "username", "id": 1, "state": "active",
+            "avatar_url": null, "web_url": null}}, "assignee": null, "source_project_id": 3,
+            "target_project_id": 3, "labels": [], "work_in_progress": false, "milestone": null,
+            "merge_status": "can_be_merged", "user_notes_count": 0,
+            "web_url": "https://gitlab.example.com/group/project/merge_requests/{iid}"}}"#,
+                iid = iid)
+    }
+
+    #[test]
+    fn get_project_direct() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        assert_eq!(gl.get_project("group", "project").unwrap().id, 3);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url,
+                   "https://gitlab.example.com/api/v3/projects/group%2Fproject");
+    }
+
+    #[test]
+    fn get_project_fallback_to_search() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(404, r#"{"message": "404 Project Not Found"}"#));
+        mock.push_response(Response::new(200,
+                                         &format!("[{},{}]",
+                                                  project_json(2, "other", "project"),
+                                                  project_json(3, "group", "project"))));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        assert_eq!(gl.get_project("group", "project").unwrap().id, 3);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].url.contains("projects/search/project"));
+    }
+
+    #[test]
+    fn get_issue_direct() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+        mock.push_response(Response::new(200, &format!("[{}]", issue_json(42))));
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+        mock.push_response(Response::new(200, "[]"));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        assert_eq!(gl.get_issue("group", "project", 42).unwrap().id, 420);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url, "https://gitlab.example.com/api/v3/projects/3/issues?iid=42");
+
+        match *gl.get_issue("group", "project", 43).unwrap_err().kind() {
+            ErrorKind::NotFound(ref message) => assert_eq!(message, "issue #43 of 'group/project'"),
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn get_issue_filter_ignored() {
+        use transport::{MockTransport, Response};
+
+        // An old server sends all the issues, unfiltered.
+        let page = |first_iid: i64| {
+            let issues: Vec<String> = (first_iid..first_iid + 20).map(issue_json).collect();
+            format!("[{}]", issues.join(","))
+        };
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+        mock.push_response(Response::new(200, &page(1)));
+        mock.push_response(Response::new(200, &page(1)));
+        mock.push_response(Response::new(200, &page(21)));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        assert_eq!(gl.get_issue("group", "project", 25).unwrap().iid, 25);
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn get_merge_request_direct() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+        mock.push_response(Response::new(200, &format!("[{}]", merge_request_json(17))));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        assert_eq!(gl.get_merge_request("group", "project", 17).unwrap().id, 170);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url,
+                   "https://gitlab.example.com/api/v3/projects/3/merge_requests?iid=17");
+    }
+
+
+    #[test]
+    fn resolve() {
+        use references::Resolved;
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+        mock.push_response(Response::new(200, &format!("[{}]", merge_request_json(17))));
+        mock.push_response(Response::new(200, &project_json(3, "group", "project")));
+
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        match gl.resolve("https://gitlab.example.com/group/project/merge_requests/17").unwrap() {
+            Resolved::MergeRequest(merge_request) => assert_eq!(merge_request.iid, 17),
+            resolved => panic!("unexpected {:?}", resolved),
+        }
+        match gl.resolve("group/project").unwrap() {
+            Resolved::Project(project) => assert_eq!(project.id, 3),
+            resolved => panic!("unexpected {:?}", resolved),
+        }
+
+        // Not resolvable: nothing sent.
+        assert!(gl.resolve("#42").is_err());
+        assert!(gl.resolve("group/project~bug").is_err());
+        assert!(gl.resolve("https://gitlab.com/group/project/issues/3").is_err());
+        assert!(gl.resolve("https://gitlab.example.com:8443/group/project/issues/3").is_err());
+        match *gl.resolve("not a reference").unwrap_err().kind() {
+            ErrorKind::InvalidReference(_) => {}
+            ref kind => panic!("unexpected error kind {:?}", kind),
+        }
+        assert_eq!(mock.requests().len(), 3);
+    }
 }
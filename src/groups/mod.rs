@@ -31,6 +31,7 @@
 // use serde_urlencoded;
 
 use BuildQuery;
+use Lister;
 use Groups;
 
 pub mod owned;
@@ -94,6 +95,33 @@ pub struct GroupsLister<'a> {
 }
 
 
+impl<'a> Lister<Groups> for GroupsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of groups.
+    fn list(&self) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of groups.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<Groups>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> GroupsLister<'a> {
     pub fn new(gl: &'a ::GitLab) -> GroupsLister {
         GroupsLister {
@@ -152,11 +180,10 @@ impl<'a> GroupsLister<'a> {
 
 
     /// Commit the lister: Query GitLab and return a list of groups.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<Groups> {
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
@@ -9,9 +9,12 @@
 //! ```text
 //! GET /groups/owned
 //! ```
+//!
+//! With API v4, this is `GET /groups?owned=true`.
 
 
 use BuildQuery;
+use Lister;
 use Groups;
 
 use ::errors::*;
@@ -23,24 +26,54 @@ pub struct GroupsLister<'a> {
 }
 
 
+impl<'a> Lister<Groups> for GroupsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of groups.
+    fn list(&self) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of groups.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<Groups> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<Groups>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> GroupsLister<'a> {
     pub fn new(gl: &'a ::GitLab) -> GroupsLister {
         GroupsLister { gl: gl }
     }
 
+
     /// Commit the lister: Query GitLab and return a list of groups.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<Groups> {
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
 
 impl<'a> BuildQuery for GroupsLister<'a> {
     fn build_query(&self) -> String {
-        String::from("groups/owned")
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => String::from("groups/owned"),
+            ::ApiVersion::V4 => String::from("groups?owned=true"),
+        }
     }
 }
 
@@ -62,6 +95,16 @@ mod tests {
         assert_eq!(query, expected_string);
     }
 
+
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        assert_eq!(gl.groups().owned().build_query(), "groups?owned=true");
+    }
+
     #[test]
     fn build_query_default_split1() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
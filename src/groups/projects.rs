@@ -27,6 +27,7 @@
 use serde_urlencoded;
 
 use BuildQuery;
+use Lister;
 
 use groups::ProjectsListerInternal;
 
@@ -41,6 +42,36 @@ pub struct ProjectsLister<'a> {
 }
 
 
+impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list(&self) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> ProjectsLister<'a> {
     pub fn new(gl: &'a ::GitLab, id: i64) -> ProjectsLister {
         ProjectsLister {
@@ -90,11 +121,10 @@ impl<'a> ProjectsLister<'a> {
 
 
     /// Commit the lister: Query GitLab and return a list of projects.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<::projects::Projects> {
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
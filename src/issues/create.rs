@@ -0,0 +1,182 @@
+//! New issue
+//!
+//! https://docs.gitlab.com/ce/api/issues.html#new-issue
+//!
+//! # New issue
+//!
+//! Creates a new project issue.
+//!
+//! If the operation is successful, a status code of `201` and the newly-created issue is
+//! returned.
+//!
+//! ```text
+//! POST /projects/ID/issues
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`            | integer | yes | The ID of a project |
+//! | `title`         | string  | yes | The title of an issue |
+//! | `description`   | string  | no  | The description of an issue  |
+//! | `confidential`  | boolean | no  | Set an issue to be confidential. Default is `false`.  |
+//! | `assignee_id`   | integer | no  | The ID of a user to assign issue |
+//! | `milestone_id`  | integer | no  | The ID of a milestone to assign issue |
+//! | `labels`        | string  | no  | Comma-separated label names for an issue  |
+//! | `due_date`      | string  | no  | Date time string in the format YEAR-MONTH-DAY, e.g. `2016-03-11` |
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Serialize)]
+struct IssueCreatorInternal {
+    /// The title of an issue
+    title: String,
+    /// The description of an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Set an issue to be confidential
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidential: Option<bool>,
+    /// The ID of a user to assign issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    /// The ID of a milestone to assign issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<i64>,
+    /// Comma-separated label names for an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<String>,
+    /// Date time string in the format YEAR-MONTH-DAY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueCreator<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    internal: IssueCreatorInternal,
+}
+
+
+impl<'a> IssueCreator<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, title: String) -> IssueCreator {
+        IssueCreator {
+            gl: gl,
+            id: id,
+            internal: IssueCreatorInternal {
+                title: title,
+                description: None,
+                confidential: None,
+                assignee_id: None,
+                milestone_id: None,
+                labels: None,
+                due_date: None,
+            },
+        }
+    }
+
+
+    pub fn description(&'a mut self, description: String) -> &'a mut IssueCreator {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn confidential(&'a mut self, confidential: bool) -> &'a mut IssueCreator {
+        self.internal.confidential = Some(confidential);
+        self
+    }
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut IssueCreator {
+        self.internal.assignee_id = Some(assignee_id);
+        self
+    }
+
+    pub fn milestone_id(&'a mut self, milestone_id: i64) -> &'a mut IssueCreator {
+        self.internal.milestone_id = Some(milestone_id);
+        self
+    }
+
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut IssueCreator {
+        self.internal.labels = Some(labels.join(","));
+        self
+    }
+
+    /// Due date, in the format YEAR-MONTH-DAY (e.g. `2016-03-11`).
+    pub fn due_date(&'a mut self, due_date: String) -> &'a mut IssueCreator {
+        self.internal.due_date = Some(due_date);
+        self
+    }
+
+
+    /// Commit the creator: Create the issue on GitLab and return it.
+    pub fn create(&self) -> Result<::issues::Issue> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.internal).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues", self.id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/issues", TEST_PROJECT_ID);
+        let query = gl.issues().create(TEST_PROJECT_ID, String::from("Title")).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.issues().create(TEST_PROJECT_ID, String::from("Title"));
+        let body = serde_json::to_string(&creator.internal).unwrap();
+        assert_eq!(body, r#"{"title":"Title"}"#);
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.issues()
+                .create(TEST_PROJECT_ID, String::from("Title"))
+                .description(String::from("Description"))
+                .confidential(true)
+                .assignee_id(12)
+                .milestone_id(34)
+                .labels(vec![String::from("bug"), String::from("critical")])
+                .due_date(String::from("2017-03-11"))
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"title":"Title","description":"Description","confidential":true,"#,
+                           r#""assignee_id":12,"milestone_id":34,"labels":"bug,critical","#,
+                           r#""due_date":"2017-03-11"}"#));
+    }
+}
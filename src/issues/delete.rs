@@ -0,0 +1,77 @@
+//! Delete an issue
+//!
+//! https://docs.gitlab.com/ce/api/issues.html#delete-an-issue
+//!
+//! # Delete an issue
+//!
+//! Only for admins and project owners. Soft deletes the issue in question.
+//!
+//! ```text
+//! DELETE /projects/ID/issues/ISSUE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of a project's issue (its `iid` with API v4) |
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct IssueDeleter<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a project's issue (its `iid` with API v4)
+    issue_id: i64,
+}
+
+
+impl<'a> IssueDeleter<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64) -> IssueDeleter {
+        IssueDeleter {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+        }
+    }
+
+    /// Commit the deleter: Delete the issue on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueDeleter<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}", self.id, self.issue_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_ISSUE_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/issues/{}", TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let query = gl.issues().delete(TEST_PROJECT_ID, TEST_ISSUE_ID).build_query();
+        assert_eq!(query, expected_string);
+    }
+}
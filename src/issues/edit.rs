@@ -0,0 +1,211 @@
+//! Edit issue
+//!
+//! https://docs.gitlab.com/ce/api/issues.html#edit-issue
+//!
+//! # Edit issue
+//!
+//! Updates an existing project issue. This call is also used to mark an issue as closed (or
+//! reopen it).
+//!
+//! If the operation is successful, a status code of `200` and the updated issue is returned.
+//!
+//! ```text
+//! PUT /projects/ID/issues/ISSUE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`            | integer | yes | The ID of a project |
+//! | `issue_id`      | integer | yes | The ID of a project's issue (its `iid` with API v4) |
+//! | `title`         | string  | no  | The title of an issue |
+//! | `description`   | string  | no  | The description of an issue  |
+//! | `confidential`  | boolean | no  | Updates an issue to be confidential |
+//! | `assignee_id`   | integer | no  | The ID of a user to assign the issue to |
+//! | `milestone_id`  | integer | no  | The ID of a milestone to assign the issue to |
+//! | `labels`        | string  | no  | Comma-separated label names for an issue  |
+//! | `state_event`   | string  | no  | The state event of an issue. Set `close` to close the issue and `reopen` to reopen it |
+//! | `due_date`      | string  | no  | Date time string in the format YEAR-MONTH-DAY, e.g. `2016-03-11` |
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct IssueEditorInternal {
+    /// The title of an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// The description of an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Updates an issue to be confidential
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidential: Option<bool>,
+    /// The ID of a user to assign the issue to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    /// The ID of a milestone to assign the issue to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<i64>,
+    /// Comma-separated label names for an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<String>,
+    /// The state event of an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_event: Option<::issues::StateEvent>,
+    /// Date time string in the format YEAR-MONTH-DAY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueEditor<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a project's issue (its `iid` with API v4)
+    issue_id: i64,
+    internal: IssueEditorInternal,
+}
+
+
+impl<'a> IssueEditor<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64) -> IssueEditor {
+        IssueEditor {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            internal: IssueEditorInternal::default(),
+        }
+    }
+
+
+    pub fn title(&'a mut self, title: String) -> &'a mut IssueEditor {
+        self.internal.title = Some(title);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut IssueEditor {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn confidential(&'a mut self, confidential: bool) -> &'a mut IssueEditor {
+        self.internal.confidential = Some(confidential);
+        self
+    }
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut IssueEditor {
+        self.internal.assignee_id = Some(assignee_id);
+        self
+    }
+
+    pub fn milestone_id(&'a mut self, milestone_id: i64) -> &'a mut IssueEditor {
+        self.internal.milestone_id = Some(milestone_id);
+        self
+    }
+
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut IssueEditor {
+        self.internal.labels = Some(labels.join(","));
+        self
+    }
+
+    pub fn state_event(&'a mut self, state_event: ::issues::StateEvent) -> &'a mut IssueEditor {
+        self.internal.state_event = Some(state_event);
+        self
+    }
+
+    /// Due date, in the format YEAR-MONTH-DAY (e.g. `2016-03-11`).
+    pub fn due_date(&'a mut self, due_date: String) -> &'a mut IssueEditor {
+        self.internal.due_date = Some(due_date);
+        self
+    }
+
+
+    /// Commit the editor: Update the issue on GitLab and return it.
+    pub fn edit(&self) -> Result<::issues::Issue> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.internal).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}", self.id, self.issue_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_ISSUE_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/issues/{}", TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let query = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let editor = gl.issues().edit(TEST_PROJECT_ID, TEST_ISSUE_ID);
+        let body = serde_json::to_string(&editor.internal).unwrap();
+        assert_eq!(body, "{}");
+    }
+
+
+    #[test]
+    fn body_state_event() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.issues()
+                .edit(TEST_PROJECT_ID, TEST_ISSUE_ID)
+                .state_event(::issues::StateEvent::Close)
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"state_event":"close"}"#);
+
+        let body = serde_json::to_string(&gl.issues()
+                .edit(TEST_PROJECT_ID, TEST_ISSUE_ID)
+                .state_event(::issues::StateEvent::Reopen)
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"state_event":"reopen"}"#);
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.issues()
+                .edit(TEST_PROJECT_ID, TEST_ISSUE_ID)
+                .title(String::from("Title"))
+                .labels(vec![String::from("bug"), String::from("critical")])
+                .assignee_id(12)
+                .confidential(false)
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"title":"Title","confidential":false,"assignee_id":12,"#,
+                           r#""labels":"bug,critical"}"#));
+    }
+}
@@ -31,6 +31,7 @@
 use serde_urlencoded;
 
 use BuildQuery;
+use Lister;
 
 use ::errors::*;
 
@@ -44,6 +45,33 @@ pub struct IssuesLister<'a> {
 }
 
 
+impl<'a> Lister<::issues::Issues> for IssuesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of issues.
+    fn list(&self) -> Result<::issues::Issues> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of issues.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::issues::Issues> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<::issues::Issues>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> IssuesLister<'a> {
     pub fn new(gl: &'a ::GitLab, id: i64) -> IssuesLister {
         IssuesLister {
@@ -87,11 +115,10 @@ impl<'a> IssuesLister<'a> {
 
 
     /// Commit the lister: Query GitLab and return a list of issues.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<::issues::Issues> {
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
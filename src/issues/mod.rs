@@ -24,7 +24,12 @@
 use BuildQuery;
 use Lister;
 
+pub mod create;
+pub mod delete;
+pub mod edit;
 pub mod group;
+pub mod note;
+pub mod notes;
 pub mod project;
 pub mod single;
 
@@ -40,6 +45,16 @@ pub enum State {
 }
 
 
+/// Change of state to apply when editing an issue.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum StateEvent {
+    #[serde(rename = "close")]
+    Close,
+    #[serde(rename = "reopen")]
+    Reopen,
+}
+
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ListingOrderBy {
     #[serde(rename = "created_at")]
@@ -112,17 +127,20 @@ pub struct Issue {
     pub title: String,
     pub description: String,
     pub state: IssueState,
-    pub created_at: String,  // FIXME: Use date type?
-    pub updated_at: String,  // FIXME: Use date type?
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    #[serde(with = "::dates::datetime")]
+    pub updated_at: ::dates::DateTime,
     pub labels: Vec<String>,
     pub milestone: Option<::Milestone>,
     pub assignee: Option<::User>,
     pub author: ::User,
-    pub subscribed: bool,
+    pub subscribed: Option<bool>,  // Not in API v4 lists
     pub user_notes_count: i64,
     pub upvotes: i64,
     pub downvotes: i64,
-    pub due_date: Option<String>,  // FIXME: Use date type?
+    #[serde(default, with = "::dates::optional_date")]
+    pub due_date: Option<::dates::Date>,
     pub confidential: bool,
     pub web_url: Option<String>
 }
@@ -131,6 +149,32 @@ pub struct Issue {
 pub type Issues = Vec<Issue>;
 
 
+impl<'a> Issue {
+    /// ID of the issue in the paths of the API: its `id` with API v3, its `iid` with API v4.
+    pub fn api_id(&self, gl: &::GitLab) -> i64 {
+        match gl.get_api_version() {
+            ::ApiVersion::V3 => self.id,
+            ::ApiVersion::V4 => self.iid,
+        }
+    }
+
+    /// Return an editor for the issue
+    pub fn edit(&'a self, gl: &'a ::GitLab) -> edit::IssueEditor {
+        edit::IssueEditor::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return a deleter for the issue
+    pub fn delete(&'a self, gl: &'a ::GitLab) -> delete::IssueDeleter {
+        delete::IssueDeleter::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return a lister for the issue's notes
+    pub fn notes(&'a self, gl: &'a ::GitLab) -> notes::IssueNotesLister {
+        notes::IssueNotesLister::new(gl, self.project_id, self.api_id(gl))
+    }
+}
+
+
 #[derive(Debug, Clone)]
 pub struct IssuesLister<'a> {
     gl: &'a ::GitLab,
@@ -154,6 +198,14 @@ impl<'a> Lister<Issues> for IssuesLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<Issues>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -181,11 +233,38 @@ impl<'a> IssuesLister<'a> {
         project::IssuesLister::new(self.gl, id)
     }
 
+    /// Return a lister for a single issue. `issue_id` is the issue's `id` with API v3, its `iid`
+    /// with API v4.
     pub fn single(self, id: i64, issue_id: i64) -> single::IssueLister<'a> {
         // assert_eq!(self, IssuesLister::new(self.gl));
         single::IssueLister::new(self.gl, id, issue_id)
     }
 
+    pub fn create(self, id: i64, title: String) -> create::IssueCreator<'a> {
+        // assert_eq!(self, IssuesLister::new(self.gl));
+        create::IssueCreator::new(self.gl, id, title)
+    }
+
+    /// Return an editor for an issue. `issue_id` is the issue's `id` with API v3, its `iid` with
+    /// API v4.
+    pub fn edit(self, id: i64, issue_id: i64) -> edit::IssueEditor<'a> {
+        // assert_eq!(self, IssuesLister::new(self.gl));
+        edit::IssueEditor::new(self.gl, id, issue_id)
+    }
+
+    /// Return a deleter for an issue. `issue_id` is the issue's `id` with API v3, its `iid` with
+    /// API v4.
+    pub fn delete(self, id: i64, issue_id: i64) -> delete::IssueDeleter<'a> {
+        // assert_eq!(self, IssuesLister::new(self.gl));
+        delete::IssueDeleter::new(self.gl, id, issue_id)
+    }
+
+    /// Return a lister for the notes of an issue. `issue_id` is the issue's `id` with API v3,
+    /// its `iid` with API v4.
+    pub fn notes(self, id: i64, issue_id: i64) -> notes::IssueNotesLister<'a> {
+        notes::IssueNotesLister::new(self.gl, id, issue_id)
+    }
+
 
     pub fn state(&'a mut self, state: State) -> &'a mut IssuesLister {
         self.internal.state = Some(state);
@@ -385,4 +464,30 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn issue_helpers() {
+        use serde_json;
+
+        let issue: ::issues::Issue = serde_json::from_str(r#"{"id": 4560, "iid": 456,
+            "project_id": 123, "title": "Title", "description": "", "state": "opened",
+            "created_at": "2016-01-04T15:31:51.081Z", "updated_at": "2016-01-04T15:31:51.081Z",
+            "labels": [], "milestone": null, "assignee": null,
+            "author": {"name": "Alexandra Bashirian", "username": "eileen.lowe", "id": 18,
+                       "state": "active", "avatar_url": null, "web_url": null},
+            "user_notes_count": 1, "upvotes": 0, "downvotes": 0, "confidential": false}"#)
+            .unwrap();
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        assert_eq!(issue.api_id(&gl), 4560);
+        assert_eq!(issue.edit(&gl).build_query(), "projects/123/issues/4560");
+        assert_eq!(issue.notes(&gl).build_query(), "projects/123/issues/4560/notes");
+
+        let gl = gl.api_version(::ApiVersion::V4);
+        assert_eq!(issue.api_id(&gl), 456);
+        assert_eq!(issue.edit(&gl).build_query(), "projects/123/issues/456");
+        assert_eq!(issue.delete(&gl).build_query(), "projects/123/issues/456");
+        assert_eq!(issue.notes(&gl).build_query(), "projects/123/issues/456/notes");
+    }
 }
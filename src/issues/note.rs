@@ -0,0 +1,184 @@
+//! Single issue note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#get-single-issue-note
+//!
+//! # Get single issue note
+//!
+//! Returns a single note for a specific project issue.
+//!
+//! ```text
+//! GET /projects/ID/issues/ISSUE_ID/notes/NOTE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of an issue (its `iid` with API v4) |
+//! | `note_id` | integer | yes   | The ID of a note |
+//!
+//! # Modify existing issue note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#modify-existing-issue-note
+//!
+//! Modify existing note of an issue.
+//!
+//! ```text
+//! PUT /projects/ID/issues/ISSUE_ID/notes/NOTE_ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of an issue (its `iid` with API v4) |
+//! | `note_id` | integer | yes   | The ID of a note |
+//! | `body`    | string  | yes   | The content of a note |
+//!
+//! # Delete an issue note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#delete-an-issue-note
+//!
+//! Deletes an existing note of an issue.
+//!
+//! ```text
+//! DELETE /projects/ID/issues/ISSUE_ID/notes/NOTE_ID
+//! ```
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct IssueNoteLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of an issue (its `iid` with API v4)
+    issue_id: i64,
+    /// The ID of a note
+    note_id: i64,
+}
+
+
+impl<'a> IssueNoteLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64, note_id: i64) -> IssueNoteLister {
+        IssueNoteLister {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            note_id: note_id,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the note.
+    pub fn list(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Return an editor replacing the body of the note
+    pub fn edit(self, body: String) -> IssueNoteEditor<'a> {
+        IssueNoteEditor::new(self.gl, self.id, self.issue_id, self.note_id, body)
+    }
+
+    /// Delete the note on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueNoteLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}/notes/{}", self.id, self.issue_id, self.note_id)
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct IssueNoteEditorInternal {
+    /// The content of a note
+    body: String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueNoteEditor<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of an issue (its `iid` with API v4)
+    issue_id: i64,
+    /// The ID of a note
+    note_id: i64,
+    internal: IssueNoteEditorInternal,
+}
+
+
+impl<'a> IssueNoteEditor<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               issue_id: i64,
+               note_id: i64,
+               body: String)
+               -> IssueNoteEditor {
+        IssueNoteEditor {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            note_id: note_id,
+            internal: IssueNoteEditorInternal { body: body },
+        }
+    }
+
+
+    /// Commit the editor: Update the note on GitLab and return it.
+    pub fn edit(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.internal).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueNoteEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}/notes/{}", self.id, self.issue_id, self.note_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_ISSUE_ID: i64 = 456;
+    const TEST_NOTE_ID: i64 = 789;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/issues/{}/notes/{}",
+                                      TEST_PROJECT_ID,
+                                      TEST_ISSUE_ID,
+                                      TEST_NOTE_ID);
+        let notes = || gl.issues().notes(TEST_PROJECT_ID, TEST_ISSUE_ID);
+        assert_eq!(notes().single(TEST_NOTE_ID).build_query(), expected_string);
+        assert_eq!(notes().edit(TEST_NOTE_ID, String::from("Body")).build_query(),
+                   expected_string);
+        assert_eq!(notes().single(TEST_NOTE_ID).edit(String::from("Body")).build_query(),
+                   expected_string);
+    }
+}
@@ -0,0 +1,321 @@
+//! List issue notes
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#list-project-issue-notes
+//!
+//! # List project issue notes
+//!
+//! Gets a list of all notes for a single issue.
+//!
+//! ```text
+//! GET /projects/ID/issues/ISSUE_ID/notes
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of an issue (its `iid` with API v4) |
+//! | `order_by`| string  | no    | Return notes ordered by `created_at` or `updated_at` fields. Default is `created_at` |
+//! | `sort`    | string  | no    | Return notes sorted in `asc` or `desc` order. Default is `desc` |
+//!
+//! # Create new issue note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#create-new-issue-note
+//!
+//! Creates a new note to a single project issue.
+//!
+//! ```text
+//! POST /projects/ID/issues/ISSUE_ID/notes
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id`      | integer | yes   | The ID of a project |
+//! | `issue_id`| integer | yes   | The ID of an issue (its `iid` with API v4) |
+//! | `body`    | string  | yes   | The content of a note |
+//! | `created_at` | string | no  | Date time string, ISO 8601 formatted, e.g. `2016-03-11T03:45:40Z` (admins and project owners only) |
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gitlab_api::GitLab;
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+//!
+//! let issue = gl.get_issue("group", "project", 42).unwrap();
+//! issue.notes(&gl).create(String::from("Thanks for the report!")).create().unwrap();
+//! ```
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct IssueNotesListerInternal {
+    /// Return notes ordered by. Default is `ListingOrderBy::CreatedAt`.
+    order_by: Option<::issues::ListingOrderBy>,
+    /// Return notes sorted. Default is `::ListingSort::Desc`.
+    sort: Option<::ListingSort>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueNotesLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of an issue (its `iid` with API v4)
+    issue_id: i64,
+    internal: IssueNotesListerInternal,
+}
+
+
+impl<'a> Lister<::Notes> for IssueNotesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of notes.
+    fn list(&self) -> Result<::Notes> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of notes.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::Notes> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<::Notes>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> IssueNotesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64) -> IssueNotesLister {
+        IssueNotesLister {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            internal: IssueNotesListerInternal::default(),
+        }
+    }
+
+
+    /// Return a lister for a single note of the issue
+    pub fn single(self, note_id: i64) -> ::issues::note::IssueNoteLister<'a> {
+        ::issues::note::IssueNoteLister::new(self.gl, self.id, self.issue_id, note_id)
+    }
+
+    /// Return a creator of a note on the issue
+    pub fn create(self, body: String) -> IssueNoteCreator<'a> {
+        IssueNoteCreator::new(self.gl, self.id, self.issue_id, body)
+    }
+
+    /// Return an editor replacing the body of a note of the issue
+    pub fn edit(self, note_id: i64, body: String) -> ::issues::note::IssueNoteEditor<'a> {
+        ::issues::note::IssueNoteEditor::new(self.gl, self.id, self.issue_id, note_id, body)
+    }
+
+    /// Delete a note of the issue on GitLab.
+    pub fn delete(self, note_id: i64) -> Result<()> {
+        self.single(note_id).delete()
+    }
+
+
+    pub fn order_by(&'a mut self,
+                    order_by: ::issues::ListingOrderBy)
+                    -> &'a mut IssueNotesLister {
+        self.internal.order_by = Some(order_by);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut IssueNotesLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+}
+
+
+impl<'a> BuildQuery for IssueNotesLister<'a> {
+    fn build_query(&self) -> String {
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        ::join_query(&format!("projects/{}/issues/{}/notes", self.id, self.issue_id),
+                     &[&encoded])
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct IssueNoteCreatorInternal {
+    /// The content of a note
+    body: String,
+    /// Date time string, ISO 8601 formatted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IssueNoteCreator<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of an issue (its `iid` with API v4)
+    issue_id: i64,
+    internal: IssueNoteCreatorInternal,
+}
+
+
+impl<'a> IssueNoteCreator<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, issue_id: i64, body: String) -> IssueNoteCreator {
+        IssueNoteCreator {
+            gl: gl,
+            id: id,
+            issue_id: issue_id,
+            internal: IssueNoteCreatorInternal {
+                body: body,
+                created_at: None,
+            },
+        }
+    }
+
+
+    /// Creation date, ISO 8601 formatted (e.g. `2016-03-11T03:45:40Z`). Requires admin or
+    /// project owner rights.
+    pub fn created_at(&'a mut self, created_at: String) -> &'a mut IssueNoteCreator {
+        self.internal.created_at = Some(created_at);
+        self
+    }
+
+
+    /// Commit the creator: Create the note on GitLab and return it.
+    pub fn create(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.internal).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for IssueNoteCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/issues/{}/notes", self.id, self.issue_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_ISSUE_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/issues/{}/notes",
+                                      TEST_PROJECT_ID,
+                                      TEST_ISSUE_ID);
+        let query = gl.issues().notes(TEST_PROJECT_ID, TEST_ISSUE_ID).build_query();
+        assert_eq!(query, expected_string);
+
+        let query = gl.issues()
+            .notes(TEST_PROJECT_ID, TEST_ISSUE_ID)
+            .create(String::from("Body"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.issues()
+            .notes(TEST_PROJECT_ID, TEST_ISSUE_ID)
+            .order_by(::issues::ListingOrderBy::UpdatedAt)
+            .sort(::ListingSort::Asc)
+            .build_query();
+        assert_eq!(query,
+                   format!("projects/{}/issues/{}/notes?order_by=updated_at&sort=asc",
+                           TEST_PROJECT_ID,
+                           TEST_ISSUE_ID));
+    }
+
+
+    #[test]
+    fn body_create() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.issues().notes(TEST_PROJECT_ID, TEST_ISSUE_ID).create(String::from("Hi"));
+        assert_eq!(serde_json::to_string(&creator.internal).unwrap(), r#"{"body":"Hi"}"#);
+
+        let body = serde_json::to_string(&gl.issues()
+                .notes(TEST_PROJECT_ID, TEST_ISSUE_ID)
+                .create(String::from("Hi"))
+                .created_at(String::from("2016-03-11T03:45:40Z"))
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"body":"Hi","created_at":"2016-03-11T03:45:40Z"}"#);
+    }
+
+
+    #[test]
+    fn notes_through_transport() {
+        use Lister;
+        use transport::{Method, MockTransport, Response};
+
+        let note_json = r#"{"id": 302, "body": "closed", "attachment": null,
+            "author": {"id": 1, "username": "pipin", "email": "admin@example.com",
+                       "name": "Pip", "state": "active", "avatar_url": null,
+                       "web_url": "http://localhost/pipin"},
+            "created_at": "2013-10-02T09:22:45Z", "updated_at": "2013-10-02T10:22:45Z",
+            "system": true, "noteable_id": 377, "noteable_type": "Issue"}"#;
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &format!("[{}]", note_json)));
+        mock.push_response(Response::new(201, note_json));
+        mock.push_response(Response::new(200, note_json));
+        mock.push_response(Response::new(200, note_json));
+        mock.push_response(Response::new(204, ""));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let notes = || gl.issues().notes(TEST_PROJECT_ID, TEST_ISSUE_ID);
+
+        let listed = notes().list().unwrap();
+        assert_eq!(listed[0].id, 302);
+        assert!(listed[0].system);
+        assert_eq!(listed[0].author.username, "pipin");
+
+        notes().create(String::from("Hello")).create().unwrap();
+        notes().single(302).list().unwrap();
+        notes().edit(302, String::from("Bye")).edit().unwrap();
+        notes().delete(302).unwrap();
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|request| request.method).collect();
+        assert_eq!(methods,
+                   vec![Method::Get, Method::Post, Method::Get, Method::Put, Method::Delete]);
+        assert_eq!(requests[1].body.as_ref().unwrap(), r#"{"body":"Hello"}"#);
+        assert_eq!(requests[3].body.as_ref().unwrap(), r#"{"body":"Bye"}"#);
+        assert_eq!(requests[4].url,
+                   "https://localhost/api/v3/projects/123/issues/456/notes/302");
+    }
+}
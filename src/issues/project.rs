@@ -62,6 +62,14 @@ impl<'a> Lister<::issues::Issues> for IssuesLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<::issues::Issues>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -143,7 +151,10 @@ impl<'a> BuildQuery for IssuesLister<'a> {
             query.push_str(split_char);
             split_char = &amp_char;
 
-            query.push_str("iid=");
+            query.push_str(match self.gl.get_api_version() {
+                ::ApiVersion::V3 => "iid=",
+                ::ApiVersion::V4 => "iids[]=",
+            });
             query.push_str(&iid.to_string());
         });
 
@@ -247,6 +258,18 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_iid_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let expected_string = format!("projects/{}/issues?iids[]=42", TEST_PROJECT_ID);
+        let query = gl.issues().project(TEST_PROJECT_ID).iid(42).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_milestone() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -13,7 +13,7 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id`      | integer | yes   | The ID of a project |
-//! | `issue_id`| integer | yes   | The ID of a project's issue |
+//! | `issue_id`| integer | yes   | The ID of a project's issue (its `iid` with API v4) |
 //!
 //!
 
@@ -28,7 +28,7 @@ pub struct IssueLister<'a> {
     gl: &'a ::GitLab,
     /// The ID of a project
     id: i64,
-    /// The ID of a project's issue
+    /// The ID of a project's issue (its `iid` with API v4)
     issue_id: i64,
 }
 
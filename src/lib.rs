@@ -12,7 +12,114 @@ extern crate error_chain;
 // `error_chain!` creates.
 pub mod errors {
     // Create the Error, ErrorKind, ResultExt, and Result types
-    error_chain!{}
+    error_chain!{
+        errors {
+            /// The request could not be sent or its response could not be read.
+            Transport(message: String) {
+                description("HTTP transport failure")
+                display("transport failure: {}", message)
+            }
+            /// The body sent by GitLab could not be deserialized; carries an excerpt of the body.
+            Deserialization(excerpt: String) {
+                description("cannot build Rust struct from JSON data")
+                display("cannot build Rust struct from JSON data: {}", excerpt)
+            }
+
+            // The following kinds carry the `message` (or `error`) field of the JSON body sent
+            // by GitLab along with the status code, or an excerpt of the body if there is none.
+
+            /// `401 Unauthorized`: the token is missing, invalid, expired or revoked.
+            Unauthorized(message: String) {
+                description("unauthorized")
+                display("unauthorized: {}", message)
+            }
+            /// `403 Forbidden`: the user is not allowed to perform the request.
+            Forbidden(message: String) {
+                description("forbidden")
+                display("forbidden: {}", message)
+            }
+            /// `404 Not Found`: the resource does not exist or is not visible to the user.
+            NotFound(message: String) {
+                description("not found")
+                display("not found: {}", message)
+            }
+            /// `409 Conflict`: the resource conflicts with an existing one.
+            Conflict(message: String) {
+                description("conflict")
+                display("conflict: {}", message)
+            }
+            /// `429 Too Many Requests`, with the number of seconds to wait from `Retry-After`.
+            RateLimited(retry_after: Option<u64>, message: String) {
+                description("rate limited")
+                display("rate limited (retry after {:?} seconds): {}", retry_after, message)
+            }
+            /// `5xx`: GitLab failed to handle the request.
+            ServerError(status: u16, message: String) {
+                description("server error")
+                display("server error '{}': {}", status, message)
+            }
+            /// Any other non-success status code.
+            HttpStatus(status: u16, message: String) {
+                description("unexpected HTTP status code")
+                display("status code '{}', not '2xx Success': {}", status, message)
+            }
+
+            /// The string is neither a GitLab web URL nor a reference (`group/project#42`).
+            InvalidReference(reference: String) {
+                description("invalid GitLab URL or reference")
+                display("invalid GitLab URL or reference: '{}'", reference)
+            }
+
+            /// The text is not a unified diff; carries the number of the offending line.
+            InvalidDiff(line: usize, reason: String) {
+                description("invalid unified diff")
+                display("invalid unified diff at line {}: {}", line, reason)
+            }
+
+            /// The merge request cannot be merged (conflicts, work in progress, failed build...).
+            MergeRequestNotMergeable {
+                description("merge request cannot be merged")
+                display("merge request cannot be merged")
+            }
+            /// The merge request is already merged or closed.
+            MergeRequestNotOpen {
+                description("merge request is already merged or closed")
+                display("merge request is already merged or closed")
+            }
+            /// The `sha` given does not match the HEAD of the merge request's source branch.
+            MergeRequestShaMismatch {
+                description("SHA does not match HEAD of source branch")
+                display("SHA does not match HEAD of source branch")
+            }
+            /// The merge request is not set to be merged when the build succeeds.
+            MergeRequestNotMergingWhenBuildSucceeds {
+                description("merge request is not set to merge when build succeeds")
+                display("merge request is not set to merge when build succeeds")
+            }
+            /// Another merge request already exists for the same source branch.
+            MergeRequestAlreadyExists {
+                description("merge request already exists")
+                display("merge request already exists")
+            }
+        }
+    }
+
+    impl ErrorKind {
+        /// The HTTP status code GitLab answered with, if the error comes from a non-success
+        /// status code.
+        pub fn status(&self) -> Option<u16> {
+            match *self {
+                ErrorKind::Unauthorized(_) => Some(401),
+                ErrorKind::Forbidden(_) => Some(403),
+                ErrorKind::NotFound(_) => Some(404),
+                ErrorKind::Conflict(_) => Some(409),
+                ErrorKind::RateLimited(..) => Some(429),
+                ErrorKind::ServerError(status, _) |
+                ErrorKind::HttpStatus(status, _) => Some(status),
+                _ => None,
+            }
+        }
+    }
 }
 
 use ::errors::*;
@@ -21,18 +128,32 @@ use ::errors::*;
 extern crate log;
 extern crate hyper;
 
-extern crate regex;
 extern crate url;
+extern crate futures;
+extern crate futures_cpupool;
+extern crate rand;
+#[cfg(feature = "chrono")]
+extern crate chrono;
 
 
+pub mod async_gitlab;
+pub mod dates;
+pub mod diff;
+pub mod events;
 pub mod gitlab;
 pub mod groups;
 pub mod projects;
 pub mod issues;
 pub mod merge_requests;
+pub mod pagination;
+pub mod rate_limit;
+pub mod references;
+pub mod retry;
+pub mod transport;
 
 // Re-export those structs
-pub use gitlab::GitLab;
+pub use async_gitlab::AsyncGitLab;
+pub use gitlab::{ApiVersion, Auth, GitLab};
 // pub use projects::Project;
 // Re-export those traits
 
@@ -90,7 +211,8 @@ pub struct Group {
     pub name: String,
     pub path: String,
     pub description: String,
-    pub visibility_level: i64,
+    pub visibility_level: Option<i64>,  // API v3 only
+    pub visibility: Option<ListingVisibility>,  // API v4 only
     pub lfs_enabled: bool,
     pub avatar_url: Option<String>,
     pub web_url: String,
@@ -108,9 +230,12 @@ pub struct Milestone {
     pub title: String,
     pub description: String,
     pub state: MilestoneState,
-    pub created_at: String,  // FIXME: Use date type?
-    pub updated_at: String,  // FIXME: Use date type?
-    pub due_date: Option<String>  // FIXME: Use date type?
+    #[serde(with = "dates::datetime")]
+    pub created_at: dates::DateTime,
+    #[serde(with = "dates::datetime")]
+    pub updated_at: dates::DateTime,
+    #[serde(default, with = "dates::optional_date")]
+    pub due_date: Option<dates::Date>,
 }
 
 
@@ -125,14 +250,191 @@ pub struct User {
 }
 
 
+/// A note (comment) on an issue, a merge request, a snippet or a commit.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Note {
+    pub id: i64,
+    pub body: String,
+    pub attachment: Option<String>,
+    pub author: User,
+    #[serde(with = "dates::datetime")]
+    pub created_at: dates::DateTime,
+    #[serde(default, with = "dates::optional_datetime")]
+    pub updated_at: Option<dates::DateTime>,
+    pub system: bool,
+    pub noteable_id: Option<i64>,
+    pub noteable_type: Option<String>,
+    /// `DiffNote` or `DiscussionNote` for notes of discussions, `null` otherwise
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Where the note is in the diff of a merge request (diff notes only)
+    pub position: Option<NotePosition>,
+    pub resolvable: Option<bool>,
+    pub resolved: Option<bool>,
+    pub resolved_by: Option<User>,
+}
+
+pub type Notes = Vec<Note>;
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionType {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "image")]
+    Image,
+}
+
+
+/// Position of a diff note in the diff of a merge request.
+///
+/// The three SHAs come from the merge request's diff version the note refers to. `old_line` is
+/// the line number before the change and `new_line` after it: a removed line only has an
+/// `old_line`, an added line only a `new_line` and an unchanged line both.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotePosition {
+    pub base_sha: String,
+    pub start_sha: String,
+    pub head_sha: String,
+    pub position_type: PositionType,
+    pub old_path: String,
+    pub new_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<i64>,
+}
+
+
+/// A commit, as found in branches and merge requests.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Commit {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub title: Option<String>,
+    pub message: Option<String>,
+    pub parent_ids: Option<Vec<String>>,
+    pub author_name: String,
+    pub author_email: String,
+    #[serde(default, with = "dates::optional_datetime")]
+    pub authored_date: Option<dates::DateTime>,
+    pub committer_name: Option<String>,
+    pub committer_email: Option<String>,
+    #[serde(default, with = "dates::optional_datetime")]
+    pub committed_date: Option<dates::DateTime>,
+    #[serde(default, with = "dates::optional_datetime")]
+    pub created_at: Option<dates::DateTime>,
+}
 
-trait BuildQuery {
+
+
+/// Build the query of a request: the part of the URL following `/api/vN/`.
+///
+/// Implemented by all the listers, so that `AsyncGitLab` can send their queries.
+pub trait BuildQuery {
     fn build_query(&self) -> String;
 }
 
+
+/// Append the URL-encoded `params` to `path`, skipping the empty ones.
+fn join_query(path: &str, params: &[&str]) -> String {
+    let params: Vec<&str> = params.iter().cloned().filter(|param| !param.is_empty()).collect();
+    if params.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, params.join("&"))
+    }
+}
+
+
+/// Host of `url`, followed by its port unless it is the default one of the scheme.
+fn host_and_port(url: &url::Url) -> Option<String> {
+    url.host_str().map(|host| {
+        match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        }
+    })
+}
+
+
+/// Time left before the Unix `timestamp`, zero if it is already past.
+fn until_timestamp(timestamp: i64, now: std::time::SystemTime) -> std::time::Duration {
+    if timestamp < 0 {
+        return std::time::Duration::from_secs(0);
+    }
+    let at = std::time::UNIX_EPOCH + std::time::Duration::from_secs(timestamp as u64);
+    at.duration_since(now).unwrap_or_else(|_| std::time::Duration::from_secs(0))
+}
+
+
+/// Length of `duration` in seconds, with a fractional part.
+fn duration_as_secs(duration: std::time::Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
+
+
+/// Duration of `seconds` (not negative), rounded to the nanosecond.
+fn duration_from_secs(seconds: f64) -> std::time::Duration {
+    let nanos = (seconds.fract() * 1e9).round() as u32;
+    // `Duration::new()` carries the nanoseconds over to the seconds.
+    std::time::Duration::new(seconds.trunc() as u64, nanos)
+}
+
 pub trait Lister<T> {
     fn list(&self) -> Result<T>;
     fn list_paginated(&self, page: u16, per_page: u16) -> Result<T>;
+    /// Like `list_paginated()`, but also return the pagination information sent by GitLab.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<pagination::Page<T>>;
+
+    /// Iterate over all the items, transparently fetching the next pages when needed.
+    ///
+    /// See the `pagination` module.
+    fn iter<'a, U>(&'a self) -> pagination::ListerIter<'a, Self, U>
+        where Self: Sized + Lister<Vec<U>>
+    {
+        pagination::ListerIter::new(self)
+    }
+}
+
+
+/// JSON replies shared by the tests of several modules.
+#[cfg(test)]
+mod fixtures {
+    /// A page of `count` groups, with ids starting at `first_id`.
+    pub fn groups_page(first_id: i64, count: i64) -> String {
+        let groups: Vec<String> = (first_id..first_id + count)
+            .map(|id| {
+                format!(r#"{{"id": {}, "name": "group{}", "path": "group{}",
+                    "description": "", "visibility_level": 20, "lfs_enabled": true,
+                    "avatar_url": null, "web_url": "https://gitlab.example.com/groups/group{}",
+                    "request_access_enabled": false}}"#,
+                        id,
+                        id,
+                        id,
+                        id)
+            })
+            .collect();
+        format!("[{}]", groups.join(","))
+    }
+
+
+    /// A project `namespace/name`.
+    pub fn project_json(id: i64, namespace: &str, name: &str) -> String {
+        format!(r#"{{"id": {id}, "description": "", "default_branch": "master", "tag_list": [],
+            "archived": false, "ssh_url_to_repo": "", "http_url_to_repo": "", "web_url": "",
+            "name": "{name}", "name_with_namespace": "{namespace} / {name}", "path": "{name}",
+            "path_with_namespace": "{namespace}/{name}",
+            "created_at": "2015-10-09T00:32:18.646Z",
+            "last_activity_at": "2017-01-31T14:46:26.638Z",
+            "shared_runners_enabled": true, "lfs_enabled": true, "creator_id": 1,
+            "namespace": {{"id": 1, "name": "{namespace}", "path": "{namespace}"}},
+            "avatar_url": null, "star_count": 0, "forks_count": 0, "shared_with_groups": [],
+            "request_access_enabled": true}}"#,
+                id = id,
+                namespace = namespace,
+                name = name)
+    }
 }
 
 
@@ -209,4 +511,74 @@ mod tests {
         let _: ::projects::Projects = serde_json::from_str(json_reply)
             .expect("JSON deserialization failed");
     }
+
+    #[test]
+    fn deserialize_project_v4() {
+        let json_reply = r##"[
+            {
+                "id": 517564,
+                "description": "GitLab API library and client in Rust",
+                "default_branch": "master",
+                "tag_list": [],
+                "archived": false,
+                "visibility": "private",
+                "ssh_url_to_repo": "git@gitlab.com:nbigaouette1/gitlab-api-rs.git",
+                "http_url_to_repo": "https://gitlab.com/nbigaouette1/gitlab-api-rs.git",
+                "web_url": "https://gitlab.com/nbigaouette1/gitlab-api-rs",
+                "name": "gitlab-api-rs",
+                "name_with_namespace": "Nicolas Bigaouette / gitlab-api-rs",
+                "path": "gitlab-api-rs",
+                "path_with_namespace": "nbigaouette1/gitlab-api-rs",
+                "container_registry_enabled": null,
+                "issues_enabled": true,
+                "merge_requests_enabled": true,
+                "wiki_enabled": true,
+                "jobs_enabled": false,
+                "snippets_enabled": false,
+                "created_at": "2015-10-09T00:32:18.646Z",
+                "last_activity_at": "2017-01-31T14:46:26.638Z",
+                "shared_runners_enabled": true,
+                "lfs_enabled": true,
+                "creator_id": 163821,
+                "namespace": {
+                    "id": 193119,
+                    "name": "nbigaouette1",
+                    "path": "nbigaouette1",
+                    "kind": "user",
+                    "full_path": "nbigaouette1"
+                },
+                "avatar_url": null,
+                "star_count": 0,
+                "forks_count": 0,
+                "open_issues_count": 1,
+                "public_jobs": true,
+                "shared_with_groups": [],
+                "only_allow_merge_if_pipeline_succeeds": false,
+                "request_access_enabled": true,
+                "only_allow_merge_if_all_discussions_are_resolved": null
+            }
+        ]"##;
+
+        let projects: ::projects::Projects = serde_json::from_str(json_reply)
+            .expect("JSON deserialization failed");
+        assert_eq!(projects[0].public, None);
+        assert_eq!(projects[0].jobs_enabled, Some(false));
+        assert_eq!(projects[0].public_jobs, Some(true));
+        match projects[0].visibility {
+            Some(::ListingVisibility::Private) => {}
+            ref visibility => panic!("unexpected visibility {:?}", visibility),
+        }
+    }
+
+    #[test]
+    fn deserialize_group_v4() {
+        let json_reply = r#"{"id": 1, "name": "Foobar Group", "path": "foo-bar",
+            "description": "An interesting group", "visibility": "public", "lfs_enabled": true,
+            "avatar_url": null, "web_url": "http://localhost:3000/groups/foo-bar",
+            "request_access_enabled": false, "full_name": "Foobar Group",
+            "full_path": "foo-bar", "parent_id": null}"#;
+
+        let group: ::Group = serde_json::from_str(json_reply).expect("JSON deserialization failed");
+        assert_eq!(group.visibility_level, None);
+    }
 }
@@ -0,0 +1,233 @@
+//! Accept MR
+//!
+//! [https://docs.gitlab.com/ce/api/merge\_requests.html#accept-mr](https://docs.gitlab.com/ce/api/merge_requests.html#accept-mr)
+//!
+//! # Accept MR
+//!
+//! Merge changes submitted with MR using this API.
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_ID/merge
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - ID of MR (its `iid` with API v4)
+//! - `merge_commit_message` (optional) - Custom merge commit message
+//! - `should_remove_source_branch` (optional) - if `true` removes the source branch
+//! - `merge_when_build_succeeds` (optional) - if `true` the MR is merged when the build succeeds
+//! - `sha` (optional) - if present, then this SHA must match the HEAD of the source branch, otherwise the merge will fail
+//!
+//! With API v4, `merge_when_build_succeeds` is named `merge_when_pipeline_succeeds`.
+//!
+//! If the merge succeeds you get `200 OK`.
+//!
+//! If it has some conflicts and can not be merged - you get `405` and error message
+//! 'Method Not Allowed' (`ErrorKind::MergeRequestNotMergeable`).
+//!
+//! If merge request is already merged or closed - you get `406` and error message
+//! 'Method Not Acceptable' (`ErrorKind::MergeRequestNotOpen`).
+//!
+//! If the `sha` parameter is passed and does not match the HEAD of the source - you get `409`
+//! and the error message 'SHA does not match HEAD of source branch'
+//! (`ErrorKind::MergeRequestShaMismatch`).
+//!
+//!
+
+
+use BuildQuery;
+
+use merge_requests::MergeRequest;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct MergeRequestAccepterInternal {
+    /// Custom merge commit message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_commit_message: Option<String>,
+    /// If `true` removes the source branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    should_remove_source_branch: Option<bool>,
+    /// If `true` the MR is merged when the build succeeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_when_build_succeeds: Option<bool>,
+    /// If present, then this SHA must match the HEAD of the source branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha: Option<String>,
+}
+
+
+/// Body of the request with API v4, where `merge_when_build_succeeds` was renamed.
+#[derive(Debug, Serialize)]
+struct MergeRequestAccepterInternalV4<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_commit_message: Option<&'a String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    should_remove_source_branch: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_when_pipeline_succeeds: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha: Option<&'a String>,
+}
+
+
+impl<'a, 'b> From<&'b MergeRequestAccepter<'a>> for MergeRequestAccepterInternalV4<'b> {
+    fn from(accepter: &'b MergeRequestAccepter<'a>) -> MergeRequestAccepterInternalV4<'b> {
+        MergeRequestAccepterInternalV4 {
+            merge_commit_message: accepter.internal.merge_commit_message.as_ref(),
+            should_remove_source_branch: accepter.internal.should_remove_source_branch,
+            merge_when_pipeline_succeeds: accepter.internal.merge_when_build_succeeds,
+            sha: accepter.internal.sha.as_ref(),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestAccepter<'a> {
+    gl: &'a ::GitLab,
+    id: i64,
+    /// The ID of the merge request (its `iid` with API v4)
+    mr_id: i64,
+    internal: MergeRequestAccepterInternal,
+}
+
+
+impl<'a> MergeRequestAccepter<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64) -> MergeRequestAccepter {
+        MergeRequestAccepter {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            internal: MergeRequestAccepterInternal::default(),
+        }
+    }
+
+
+    pub fn merge_commit_message(&'a mut self,
+                                merge_commit_message: String)
+                                -> &'a mut MergeRequestAccepter {
+        self.internal.merge_commit_message = Some(merge_commit_message);
+        self
+    }
+
+    pub fn should_remove_source_branch(&'a mut self,
+                                       should_remove_source_branch: bool)
+                                       -> &'a mut MergeRequestAccepter {
+        self.internal.should_remove_source_branch = Some(should_remove_source_branch);
+        self
+    }
+
+    pub fn merge_when_build_succeeds(&'a mut self,
+                                     merge_when_build_succeeds: bool)
+                                     -> &'a mut MergeRequestAccepter {
+        self.internal.merge_when_build_succeeds = Some(merge_when_build_succeeds);
+        self
+    }
+
+    /// Only merge if the HEAD of the source branch is still `sha`.
+    pub fn sha(&'a mut self, sha: String) -> &'a mut MergeRequestAccepter {
+        self.internal.sha = Some(sha);
+        self
+    }
+
+
+    /// Commit the accepter: Merge the merge request on GitLab and return it.
+    pub fn accept(&self) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let result = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => self.gl.put(&query, &self.internal),
+            ::ApiVersion::V4 => self.gl.put(&query, &MergeRequestAccepterInternalV4::from(self)),
+        };
+        let kind = |status| match status {
+            405 => Some(ErrorKind::MergeRequestNotMergeable),
+            406 => Some(ErrorKind::MergeRequestNotOpen),
+            409 => Some(ErrorKind::MergeRequestShaMismatch),
+            _ => None,
+        };
+        ::merge_requests::map_status_error(result, kind, || format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestAccepter<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/merge", self.id, self.mr_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/merge",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).accept(TEST_MR_ID).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let accepter = gl.merge_requests(TEST_PROJECT_ID).accept(TEST_MR_ID);
+        let body = serde_json::to_string(&accepter.internal).unwrap();
+        assert_eq!(body, "{}");
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.merge_requests(TEST_PROJECT_ID)
+                .accept(TEST_MR_ID)
+                .merge_commit_message(String::from("Merge feature"))
+                .should_remove_source_branch(true)
+                .merge_when_build_succeeds(true)
+                .sha(String::from("1234567890abcdef"))
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"merge_commit_message":"Merge feature","#,
+                           r#""should_remove_source_branch":true,"#,
+                           r#""merge_when_build_succeeds":true,"sha":"1234567890abcdef"}"#));
+    }
+
+
+    #[test]
+    fn body_v4() {
+        use transport::{MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(405, "{}"));
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4)
+            .transport(mock.clone());
+
+        let _ = gl.merge_requests(TEST_PROJECT_ID)
+            .accept(TEST_MR_ID)
+            .merge_when_build_succeeds(true)
+            .accept();
+        assert_eq!(mock.requests()[0].body,
+                   Some(String::from(r#"{"merge_when_pipeline_succeeds":true}"#)));
+    }
+}
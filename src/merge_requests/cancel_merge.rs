@@ -0,0 +1,125 @@
+//! Cancel Merge When Build Succeeds
+//!
+//! [https://docs.gitlab.com/ce/api/merge\_requests.html#cancel-merge-when-build-succeeds](https://docs.gitlab.com/ce/api/merge_requests.html#cancel-merge-when-build-succeeds)
+//!
+//! # Cancel Merge When Build Succeeds
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_ID/cancel_merge_when_build_succeeds
+//! ```
+//!
+//! With API v4, the endpoint is `cancel_merge_when_pipeline_succeeds`.
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - ID of MR (its `iid` with API v4)
+//!
+//! If successful you'll get `200 OK`.
+//!
+//! If you don't have permissions to accept this merge request - you'll get a 401.
+//!
+//! If the merge request is already merged or closed - you get `405` and error message
+//! 'Method Not Allowed' (`ErrorKind::MergeRequestNotOpen`).
+//!
+//! In case the merge request is not set to be merged when the build succeeds, you'll also get
+//! a `406` error (`ErrorKind::MergeRequestNotMergingWhenBuildSucceeds`).
+//!
+//!
+
+
+use BuildQuery;
+
+use merge_requests::MergeRequest;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequestMergeCancelerInternal {}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestMergeCanceler<'a> {
+    gl: &'a ::GitLab,
+    id: i64,
+    /// The ID of the merge request (its `iid` with API v4)
+    mr_id: i64,
+}
+
+
+impl<'a> MergeRequestMergeCanceler<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64) -> MergeRequestMergeCanceler {
+        MergeRequestMergeCanceler {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+        }
+    }
+
+    /// Commit the canceler: Cancel the merge when build succeeds and return the merge request.
+    pub fn cancel(&self) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let result = self.gl.post(&query, &MergeRequestMergeCancelerInternal {});
+        let kind = |status| match status {
+            405 => Some(ErrorKind::MergeRequestNotOpen),
+            406 => Some(ErrorKind::MergeRequestNotMergingWhenBuildSucceeds),
+            _ => None,
+        };
+        ::merge_requests::map_status_error(result, kind, || format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestMergeCanceler<'a> {
+    fn build_query(&self) -> String {
+        let endpoint = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => "cancel_merge_when_build_succeeds",
+            ::ApiVersion::V4 => "cancel_merge_when_pipeline_succeeds",
+        };
+        format!("projects/{}/merge_requests/{}/{}", self.id, self.mr_id, endpoint)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/\
+                                       cancel_merge_when_build_succeeds",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .cancel_merge_when_build_succeeds(TEST_MR_ID)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let expected_string = format!("projects/{}/merge_requests/{}/\
+                                       cancel_merge_when_pipeline_succeeds",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .cancel_merge_when_build_succeeds(TEST_MR_ID)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+}
@@ -0,0 +1,206 @@
+//! Create MR
+//!
+//! [https://docs.gitlab.com/ce/api/merge\_requests.html#create-mr](https://docs.gitlab.com/ce/api/merge_requests.html#create-mr)
+//!
+//! # Create MR
+//!
+//! Creates a new merge request.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `source_branch` (required) - The source branch
+//! - `target_branch` (required) - The target branch
+//! - `title` (required) - Title of MR
+//! - `assignee_id` (optional) - Assignee user ID
+//! - `description` (optional) - Description of MR
+//! - `target_project_id` (optional) - The target project (numeric id)
+//! - `labels` (optional) - Labels for MR as a comma-separated list
+//! - `milestone_id` (optional) - Milestone ID
+//! - `remove_source_branch` (optional) - Flag indicating if a merge request should remove the source branch when merging
+//!
+//! If the operation is successful, 201 and the newly created merge request is returned.
+//! If an error occurs, an error number and a message explaining the reason is returned.
+//!
+//!
+
+
+use BuildQuery;
+
+use merge_requests::MergeRequest;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequestCreatorInternal {
+    /// The source branch
+    source_branch: String,
+    /// The target branch
+    target_branch: String,
+    /// Title of MR
+    title: String,
+    /// Assignee user ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    /// Description of MR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// The target project (numeric id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_project_id: Option<i64>,
+    /// Labels for MR as a comma-separated list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<String>,
+    /// Milestone ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<i64>,
+    /// Flag indicating if a merge request should remove the source branch when merging
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_source_branch: Option<bool>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestCreator<'a> {
+    gl: &'a ::GitLab,
+    id: i64,
+    internal: MergeRequestCreatorInternal,
+}
+
+
+impl<'a> MergeRequestCreator<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               source_branch: String,
+               target_branch: String,
+               title: String)
+               -> MergeRequestCreator {
+        MergeRequestCreator {
+            gl: gl,
+            id: id,
+            internal: MergeRequestCreatorInternal {
+                source_branch: source_branch,
+                target_branch: target_branch,
+                title: title,
+                assignee_id: None,
+                description: None,
+                target_project_id: None,
+                labels: None,
+                milestone_id: None,
+                remove_source_branch: None,
+            },
+        }
+    }
+
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut MergeRequestCreator {
+        self.internal.assignee_id = Some(assignee_id);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut MergeRequestCreator {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn target_project_id(&'a mut self, target_project_id: i64) -> &'a mut MergeRequestCreator {
+        self.internal.target_project_id = Some(target_project_id);
+        self
+    }
+
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut MergeRequestCreator {
+        self.internal.labels = Some(labels.join(","));
+        self
+    }
+
+    pub fn milestone_id(&'a mut self, milestone_id: i64) -> &'a mut MergeRequestCreator {
+        self.internal.milestone_id = Some(milestone_id);
+        self
+    }
+
+    pub fn remove_source_branch(&'a mut self,
+                                remove_source_branch: bool)
+                                -> &'a mut MergeRequestCreator {
+        self.internal.remove_source_branch = Some(remove_source_branch);
+        self
+    }
+
+
+    /// Commit the creator: Create the merge request on GitLab and return it.
+    ///
+    /// A `409 Conflict` from GitLab gives an `ErrorKind::MergeRequestAlreadyExists`.
+    pub fn create(&self) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let result = self.gl.post(&query, &self.internal);
+        let kind = |status| match status {
+            409 => Some(ErrorKind::MergeRequestAlreadyExists),
+            _ => None,
+        };
+        ::merge_requests::map_status_error(result, kind, || format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests", self.id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests", TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .create(String::from("feature"), String::from("master"), String::from("Title"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.merge_requests(TEST_PROJECT_ID)
+            .create(String::from("feature"), String::from("master"), String::from("Title"));
+        let body = serde_json::to_string(&creator.internal).unwrap();
+        assert_eq!(body,
+                   r#"{"source_branch":"feature","target_branch":"master","title":"Title"}"#);
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.merge_requests(TEST_PROJECT_ID)
+                .create(String::from("feature"), String::from("master"), String::from("Title"))
+                .description(String::from("Description"))
+                .labels(vec![String::from("release")])
+                .remove_source_branch(true)
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"source_branch":"feature","target_branch":"master","#,
+                           r#""title":"Title","description":"Description","labels":"release","#,
+                           r#""remove_source_branch":true}"#));
+    }
+}
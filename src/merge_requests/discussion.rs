@@ -0,0 +1,226 @@
+//! Single merge request discussion
+//!
+//! https://docs.gitlab.com/ce/api/discussions.html#get-single-merge-request-discussion
+//!
+//! # Get single merge request discussion
+//!
+//! Returns a single discussion for a specific project merge request.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_IID/discussions/DISCUSSION_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_iid` (required) - The IID of a merge request
+//! - `discussion_id` (required) - The ID of a discussion
+//!
+//! # Resolve a merge request discussion
+//!
+//! https://docs.gitlab.com/ce/api/discussions.html#resolve-a-merge-request-discussion
+//!
+//! Resolve/unresolve whole discussion of a merge request.
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_IID/discussions/DISCUSSION_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_iid` (required) - The IID of a merge request
+//! - `discussion_id` (required) - The ID of a discussion
+//! - `resolved` (required) - Resolve/unresolve the discussion
+//!
+//! # Add note to existing merge request discussion
+//!
+//! https://docs.gitlab.com/ce/api/discussions.html#add-note-to-existing-merge-request-discussion
+//!
+//! Adds a new note to the discussion.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_IID/discussions/DISCUSSION_ID/notes
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_iid` (required) - The IID of a merge request
+//! - `discussion_id` (required) - The ID of a discussion
+//! - `body` (required) - The content of a note
+//!
+//! The discussions API only exists with API v4.
+//!
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Serialize)]
+struct DiscussionResolverInternal {
+    /// Resolve/unresolve the discussion
+    resolved: bool,
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct DiscussionNoteInternal {
+    /// The content of a note
+    body: String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestDiscussionLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The IID of a merge request
+    mr_iid: i64,
+    /// The ID of a discussion
+    discussion_id: String,
+}
+
+
+impl<'a> MergeRequestDiscussionLister<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               mr_iid: i64,
+               discussion_id: String)
+               -> MergeRequestDiscussionLister {
+        MergeRequestDiscussionLister {
+            gl: gl,
+            id: id,
+            mr_iid: mr_iid,
+            discussion_id: discussion_id,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the discussion.
+    pub fn list(&self) -> Result<::merge_requests::Discussion> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Resolve the discussion on GitLab and return it.
+    pub fn resolve(&self) -> Result<::merge_requests::Discussion> {
+        self.set_resolved(true)
+    }
+
+    /// Unresolve the discussion on GitLab and return it.
+    pub fn unresolve(&self) -> Result<::merge_requests::Discussion> {
+        self.set_resolved(false)
+    }
+
+    /// Reply to the discussion: add a note to it on GitLab and return the note.
+    pub fn add_note(&self, body: String) -> Result<::Note> {
+        let query = format!("{}/notes", self.build_query());
+        debug!("query: {:?}", query);
+
+        self.gl
+            .post(&query, &DiscussionNoteInternal { body: body })
+            .chain_err(|| format!("cannot post query {}", query))
+    }
+
+
+    fn set_resolved(&self, resolved: bool) -> Result<::merge_requests::Discussion> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl
+            .put(&query, &DiscussionResolverInternal { resolved: resolved })
+            .chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestDiscussionLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/discussions/{}",
+                self.id,
+                self.mr_iid,
+                self.discussion_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_IID: i64 = 45;
+    const TEST_DISCUSSION_ID: &'static str = "6a9c1750b37d513a43987b574953fceb50b03ce7";
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .discussions(TEST_MR_IID)
+            .single(String::from(TEST_DISCUSSION_ID))
+            .build_query();
+        assert_eq!(query,
+                   format!("projects/{}/merge_requests/{}/discussions/{}",
+                           TEST_PROJECT_ID,
+                           TEST_MR_IID,
+                           TEST_DISCUSSION_ID));
+    }
+
+
+    #[test]
+    fn discussion_through_transport() {
+        use transport::{Method, MockTransport, Response};
+
+        let note_json = r#"{"id": 1126, "type": "DiscussionNote", "body": "Done",
+            "attachment": null,
+            "author": {"id": 1, "name": "root", "username": "root", "state": "active",
+                       "avatar_url": null, "web_url": "http://localhost/root"},
+            "created_at": "2018-03-03T21:54:39.668Z", "system": false,
+            "noteable_id": 3, "noteable_type": "MergeRequest",
+            "resolved": true, "resolvable": true}"#;
+        let discussion_json = format!(r#"{{"id": "{}", "individual_note": false,
+                                           "notes": [{}]}}"#,
+                                      TEST_DISCUSSION_ID,
+                                      note_json);
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &discussion_json));
+        mock.push_response(Response::new(200, &discussion_json));
+        mock.push_response(Response::new(200, &discussion_json));
+        mock.push_response(Response::new(201, note_json));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4)
+            .transport(mock.clone());
+        let discussion = || {
+            gl.merge_requests(TEST_PROJECT_ID)
+                .discussions(TEST_MR_IID)
+                .single(String::from(TEST_DISCUSSION_ID))
+        };
+
+        assert_eq!(discussion().list().unwrap().id, TEST_DISCUSSION_ID);
+        assert_eq!(discussion().resolve().unwrap().notes[0].resolved, Some(true));
+        discussion().unresolve().unwrap();
+        assert_eq!(discussion().add_note(String::from("Done")).unwrap().body, "Done");
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|request| request.method).collect();
+        assert_eq!(methods, vec![Method::Get, Method::Put, Method::Put, Method::Post]);
+        assert_eq!(requests[1].body.as_ref().unwrap(), r#"{"resolved":true}"#);
+        assert_eq!(requests[2].body.as_ref().unwrap(), r#"{"resolved":false}"#);
+        assert_eq!(requests[3].body.as_ref().unwrap(), r#"{"body":"Done"}"#);
+        assert_eq!(requests[3].url,
+                   format!("https://localhost/api/v4/projects/123/merge_requests/45/\
+                            discussions/{}/notes",
+                           TEST_DISCUSSION_ID));
+    }
+}
@@ -0,0 +1,328 @@
+//! List merge request discussions
+//!
+//! https://docs.gitlab.com/ce/api/discussions.html#list-project-merge-request-discussions
+//!
+//! # List project merge request discussions
+//!
+//! Gets a list of all discussions for a single merge request: threads of notes, including the
+//! notes on lines of the diff.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_IID/discussions
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_iid` (required) - The IID of a merge request
+//!
+//! # Create new merge request discussion
+//!
+//! https://docs.gitlab.com/ce/api/discussions.html#create-new-merge-request-discussion
+//!
+//! Creates a new discussion to a single project merge request. A discussion with a `position`
+//! is a note on a line of the diff.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_IID/discussions
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_iid` (required) - The IID of a merge request
+//! - `body` (required) - The content of a discussion
+//! - `position` (optional) - Position when creating a diff note
+//!     - `base_sha` (required) - Base commit SHA in the source branch
+//!     - `start_sha` (required) - SHA referencing commit in target branch
+//!     - `head_sha` (required) - SHA referencing HEAD of this merge request
+//!     - `position_type` (required) - Type of the position reference: `text` or `image`
+//!     - `new_path` (required) - File path after change
+//!     - `new_line` (optional) - Line number after change
+//!     - `old_path` (required) - File path before change
+//!     - `old_line` (optional) - Line number before change
+//!
+//! The discussions API only exists with API v4.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gitlab_api::{ApiVersion, GitLab, NotePosition, PositionType};
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .api_version(ApiVersion::V4);
+//!
+//! let merge_request = gl.get_merge_request("group", "project", 17).unwrap();
+//! let position = NotePosition {
+//!     base_sha: String::from("1f2d3c4b5a6f7e8d9c0b1a2f3e4d5c6b7a8f9e0d"),
+//!     start_sha: String::from("1f2d3c4b5a6f7e8d9c0b1a2f3e4d5c6b7a8f9e0d"),
+//!     head_sha: String::from("0d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1f"),
+//!     position_type: PositionType::Text,
+//!     old_path: String::from("src/lib.rs"),
+//!     new_path: String::from("src/lib.rs"),
+//!     old_line: None,
+//!     new_line: Some(42),
+//! };
+//! merge_request.discussions(&gl)
+//!     .create(String::from("This line needs a test."))
+//!     .position(position)
+//!     .create()
+//!     .unwrap();
+//! ```
+
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestDiscussionsLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The IID of a merge request
+    mr_iid: i64,
+}
+
+
+impl<'a> Lister<::merge_requests::Discussions> for MergeRequestDiscussionsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of discussions.
+    fn list(&self) -> Result<::merge_requests::Discussions> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of discussions.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::merge_requests::Discussions> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::merge_requests::Discussions>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> MergeRequestDiscussionsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_iid: i64) -> MergeRequestDiscussionsLister {
+        MergeRequestDiscussionsLister {
+            gl: gl,
+            id: id,
+            mr_iid: mr_iid,
+        }
+    }
+
+
+    /// Return a lister for a single discussion of the merge request
+    pub fn single(self,
+                  discussion_id: String)
+                  -> ::merge_requests::discussion::MergeRequestDiscussionLister<'a> {
+        ::merge_requests::discussion::MergeRequestDiscussionLister::new(self.gl,
+                                                                        self.id,
+                                                                        self.mr_iid,
+                                                                        discussion_id)
+    }
+
+    /// Return a creator of a discussion on the merge request
+    pub fn create(self, body: String) -> MergeRequestDiscussionCreator<'a> {
+        MergeRequestDiscussionCreator::new(self.gl, self.id, self.mr_iid, body)
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestDiscussionsLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/discussions", self.id, self.mr_iid)
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequestDiscussionCreatorInternal {
+    /// The content of a discussion
+    body: String,
+    /// Position when creating a diff note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position: Option<::NotePosition>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestDiscussionCreator<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The IID of a merge request
+    mr_iid: i64,
+    internal: MergeRequestDiscussionCreatorInternal,
+}
+
+
+impl<'a> MergeRequestDiscussionCreator<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               mr_iid: i64,
+               body: String)
+               -> MergeRequestDiscussionCreator {
+        MergeRequestDiscussionCreator {
+            gl: gl,
+            id: id,
+            mr_iid: mr_iid,
+            internal: MergeRequestDiscussionCreatorInternal {
+                body: body,
+                position: None,
+            },
+        }
+    }
+
+
+    /// Attach the discussion to a line of the diff, making its first note a diff note.
+    pub fn position(&'a mut self,
+                    position: ::NotePosition)
+                    -> &'a mut MergeRequestDiscussionCreator {
+        self.internal.position = Some(position);
+        self
+    }
+
+
+    /// Commit the creator: Create the discussion on GitLab and return it.
+    pub fn create(&self) -> Result<::merge_requests::Discussion> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.internal).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestDiscussionCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/discussions", self.id, self.mr_iid)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_IID: i64 = 45;
+
+
+    fn position() -> ::NotePosition {
+        ::NotePosition {
+            base_sha: String::from("aaaa"),
+            start_sha: String::from("bbbb"),
+            head_sha: String::from("cccc"),
+            position_type: ::PositionType::Text,
+            old_path: String::from("README.md"),
+            new_path: String::from("README.md"),
+            old_line: None,
+            new_line: Some(12),
+        }
+    }
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/discussions",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_IID);
+        let discussions = || gl.merge_requests(TEST_PROJECT_ID).discussions(TEST_MR_IID);
+        assert_eq!(discussions().build_query(), expected_string);
+        assert_eq!(discussions().create(String::from("Body")).build_query(),
+                   expected_string);
+    }
+
+
+    #[test]
+    fn body_create() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.merge_requests(TEST_PROJECT_ID)
+            .discussions(TEST_MR_IID)
+            .create(String::from("Hi"));
+        assert_eq!(serde_json::to_string(&creator.internal).unwrap(), r#"{"body":"Hi"}"#);
+
+        let body = serde_json::to_string(&gl.merge_requests(TEST_PROJECT_ID)
+                .discussions(TEST_MR_IID)
+                .create(String::from("Hi"))
+                .position(position())
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"body":"Hi","position":{"base_sha":"aaaa","start_sha":"bbbb","#,
+                           r#""head_sha":"cccc","position_type":"text","old_path":"README.md","#,
+                           r#""new_path":"README.md","new_line":12}}"#));
+    }
+
+
+    #[test]
+    fn deserialize_discussions() {
+        let json_reply = r#"[
+            {"id": "6a9c1750b37d513a43987b574953fceb50b03ce7", "individual_note": false,
+             "notes": [
+                {"id": 1126, "type": "DiscussionNote", "body": "discussion text",
+                 "attachment": null,
+                 "author": {"id": 1, "name": "root", "username": "root", "state": "active",
+                            "avatar_url": null, "web_url": "http://localhost/root"},
+                 "created_at": "2018-03-03T21:54:39.668Z",
+                 "updated_at": "2018-03-03T21:54:39.668Z", "system": false,
+                 "noteable_id": 3, "noteable_type": "MergeRequest", "noteable_iid": null,
+                 "resolved": false, "resolvable": true, "resolved_by": null}
+             ]},
+            {"id": "87805b7c09016a7058e91bdbe7b29d1f284a39e6", "individual_note": false,
+             "notes": [
+                {"id": 1128, "type": "DiffNote", "body": "diff comment", "attachment": null,
+                 "author": {"id": 1, "name": "root", "username": "root", "state": "active",
+                            "avatar_url": null, "web_url": "http://localhost/root"},
+                 "created_at": "2018-03-04T09:17:22.520Z",
+                 "updated_at": "2018-03-04T09:17:22.520Z", "system": false,
+                 "noteable_id": 3, "noteable_type": "MergeRequest", "noteable_iid": null,
+                 "position": {"base_sha": "b5d6e7b1613fca24d250fa8e5bc7bcc3dd6002ef",
+                              "start_sha": "7c9c2ead8a320fb7ba0b4e234bd9529a2614e306",
+                              "head_sha": "4803c71e6b1833ca72b8b26ef2ecd5adc8a38031",
+                              "old_path": "package.json", "new_path": "package.json",
+                              "position_type": "text", "old_line": 27, "new_line": 27},
+                 "resolved": true, "resolvable": true,
+                 "resolved_by": {"id": 1, "name": "root", "username": "root",
+                                 "state": "active", "avatar_url": null,
+                                 "web_url": "http://localhost/root"}}
+             ]}
+        ]"#;
+
+        let discussions: ::merge_requests::Discussions = serde_json::from_str(json_reply)
+            .unwrap();
+
+        assert!(!discussions[0].individual_note);
+        assert_eq!(discussions[0].notes[0].type_, Some(String::from("DiscussionNote")));
+        assert!(discussions[0].notes[0].position.is_none());
+        assert_eq!(discussions[0].notes[0].resolved, Some(false));
+
+        let note = &discussions[1].notes[0];
+        let position = note.position.as_ref().unwrap();
+        assert_eq!(position.position_type, ::PositionType::Text);
+        assert_eq!(position.old_line, Some(27));
+        assert_eq!(position.new_path, "package.json");
+        assert_eq!(note.resolved_by.as_ref().unwrap().username, "root");
+    }
+}
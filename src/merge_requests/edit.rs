@@ -0,0 +1,197 @@
+//! Update MR
+//!
+//! [https://docs.gitlab.com/ce/api/merge\_requests.html#update-mr](https://docs.gitlab.com/ce/api/merge_requests.html#update-mr)
+//!
+//! # Update MR
+//!
+//! Updates an existing merge request. You can change the target branch, title, or even close the
+//! MR.
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - ID of MR (its `iid` with API v4)
+//! - `target_branch` - The target branch
+//! - `assignee_id` - Assignee user ID
+//! - `title` - Title of MR
+//! - `description` - Description of MR
+//! - `state_event` - New state (close|reopen|merge)
+//! - `labels` (optional) - Labels for MR as a comma-separated list
+//! - `milestone_id` (optional) - Milestone ID
+//! - `remove_source_branch` (optional) - Flag indicating if a merge request should remove the source branch when merging
+//!
+//! If the operation is successful, 200 and the updated merge request is returned.
+//! If an error occurs, an error number and a message explaining the reason is returned.
+//!
+//!
+
+
+use BuildQuery;
+
+use merge_requests::MergeRequest;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct MergeRequestEditorInternal {
+    /// The target branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_branch: Option<String>,
+    /// Assignee user ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee_id: Option<i64>,
+    /// Title of MR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// Description of MR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// New state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_event: Option<::merge_requests::StateEvent>,
+    /// Labels for MR as a comma-separated list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<String>,
+    /// Milestone ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<i64>,
+    /// Flag indicating if a merge request should remove the source branch when merging
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_source_branch: Option<bool>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestEditor<'a> {
+    gl: &'a ::GitLab,
+    id: i64,
+    /// The ID of the merge request (its `iid` with API v4)
+    mr_id: i64,
+    internal: MergeRequestEditorInternal,
+}
+
+
+impl<'a> MergeRequestEditor<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64) -> MergeRequestEditor {
+        MergeRequestEditor {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            internal: MergeRequestEditorInternal::default(),
+        }
+    }
+
+
+    pub fn target_branch(&'a mut self, target_branch: String) -> &'a mut MergeRequestEditor {
+        self.internal.target_branch = Some(target_branch);
+        self
+    }
+
+    pub fn assignee_id(&'a mut self, assignee_id: i64) -> &'a mut MergeRequestEditor {
+        self.internal.assignee_id = Some(assignee_id);
+        self
+    }
+
+    pub fn title(&'a mut self, title: String) -> &'a mut MergeRequestEditor {
+        self.internal.title = Some(title);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut MergeRequestEditor {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn state_event(&'a mut self,
+                       state_event: ::merge_requests::StateEvent)
+                       -> &'a mut MergeRequestEditor {
+        self.internal.state_event = Some(state_event);
+        self
+    }
+
+    pub fn labels(&'a mut self, labels: Vec<String>) -> &'a mut MergeRequestEditor {
+        self.internal.labels = Some(labels.join(","));
+        self
+    }
+
+    pub fn milestone_id(&'a mut self, milestone_id: i64) -> &'a mut MergeRequestEditor {
+        self.internal.milestone_id = Some(milestone_id);
+        self
+    }
+
+    pub fn remove_source_branch(&'a mut self,
+                                remove_source_branch: bool)
+                                -> &'a mut MergeRequestEditor {
+        self.internal.remove_source_branch = Some(remove_source_branch);
+        self
+    }
+
+
+    /// Commit the editor: Update the merge request on GitLab and return it.
+    pub fn edit(&self) -> Result<MergeRequest> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.internal).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}", self.id, self.mr_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}", TEST_PROJECT_ID, TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).edit(TEST_MR_ID).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let editor = gl.merge_requests(TEST_PROJECT_ID).edit(TEST_MR_ID);
+        let body = serde_json::to_string(&editor.internal).unwrap();
+        assert_eq!(body, "{}");
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.merge_requests(TEST_PROJECT_ID)
+                .edit(TEST_MR_ID)
+                .target_branch(String::from("stable"))
+                .title(String::from("Title"))
+                .assignee_id(12)
+                .state_event(::merge_requests::StateEvent::Close)
+                .internal)
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"target_branch":"stable","assignee_id":12,"title":"Title","#,
+                           r#""state_event":"close"}"#));
+    }
+}
@@ -29,10 +29,23 @@
 
 // use serde_urlencoded;
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+use serde_json;
+
 use BuildQuery;
 use Lister;
 
 pub mod single;
+pub mod create;
+pub mod edit;
+pub mod accept;
+pub mod cancel_merge;
+pub mod discussion;
+pub mod discussions;
+pub mod note;
+pub mod notes;
 
 use ::errors::*;
 
@@ -60,6 +73,16 @@ pub enum Status {
 }
 
 
+/// Change of state to apply when editing a merge request.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum StateEvent {
+    #[serde(rename = "close")]
+    Close,
+    #[serde(rename = "reopen")]
+    Reopen,
+}
+
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ListingOrderBy {
     #[serde(rename = "created_at")]
@@ -93,8 +116,10 @@ pub struct MergeRequest {
     pub title: String,
     pub description: String,
     pub state: State,
-    pub created_at: String,  // FIXME: Use chrono crate
-    pub updated_at: String,  // FIXME: Use chrono crate
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    #[serde(with = "::dates::datetime")]
+    pub updated_at: ::dates::DateTime,
     pub target_branch: String,
     pub source_branch: String,
     pub upvotes: i64,
@@ -106,11 +131,12 @@ pub struct MergeRequest {
     pub labels: Vec<String>,
     pub work_in_progress: bool,
     pub milestone: Option<::Milestone>,
-    pub merge_when_build_succeeds: bool,
+    pub merge_when_build_succeeds: Option<bool>,  // API v3 only
+    pub merge_when_pipeline_succeeds: Option<bool>,  // API v4 only
     pub merge_status: Status,
     pub sha: Option<String>,
     pub merge_commit_sha: Option<String>,
-    pub subscribed: bool,
+    pub subscribed: Option<bool>,  // Not in API v4 lists
     pub user_notes_count: i64,
     pub should_remove_source_branch: Option<bool>,
     pub force_remove_source_branch: Option<bool>,
@@ -120,6 +146,196 @@ pub struct MergeRequest {
 pub type MergeRequests = Vec<MergeRequest>;
 
 
+impl<'a> MergeRequest {
+    /// ID of the merge request in the paths of the API: its `id` with API v3, its `iid` with
+    /// API v4.
+    pub fn api_id(&self, gl: &::GitLab) -> i64 {
+        match gl.get_api_version() {
+            ::ApiVersion::V3 => self.id,
+            ::ApiVersion::V4 => self.iid,
+        }
+    }
+
+    /// Return a lister for the merge request, its changes, commits and diff versions
+    pub fn single(&'a self, gl: &'a ::GitLab) -> single::MergeRequestLister {
+        single::MergeRequestLister::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return an editor for the merge request
+    pub fn edit(&'a self, gl: &'a ::GitLab) -> edit::MergeRequestEditor {
+        edit::MergeRequestEditor::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return an accepter for the merge request
+    pub fn accept(&'a self, gl: &'a ::GitLab) -> accept::MergeRequestAccepter {
+        accept::MergeRequestAccepter::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return a canceler of the merge of the merge request when its build succeeds
+    pub fn cancel_merge_when_build_succeeds(&'a self,
+                                            gl: &'a ::GitLab)
+                                            -> cancel_merge::MergeRequestMergeCanceler {
+        cancel_merge::MergeRequestMergeCanceler::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return a lister for the merge request's notes
+    pub fn notes(&'a self, gl: &'a ::GitLab) -> notes::MergeRequestNotesLister {
+        notes::MergeRequestNotesLister::new(gl, self.project_id, self.api_id(gl))
+    }
+
+    /// Return a lister for the merge request's discussions (API v4 only)
+    pub fn discussions(&'a self, gl: &'a ::GitLab) -> discussions::MergeRequestDiscussionsLister {
+        discussions::MergeRequestDiscussionsLister::new(gl, self.project_id, self.iid)
+    }
+}
+
+
+/// Change of a single file in the diff of a merge request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Diff {
+    pub old_path: String,
+    pub new_path: String,
+    /// File mode before the change (e.g. `100644`), `0` or `null` for a new file
+    pub a_mode: Option<String>,
+    /// File mode after the change, `0` or `null` for a deleted file
+    pub b_mode: Option<String>,
+    pub new_file: bool,
+    pub renamed_file: bool,
+    pub deleted_file: bool,
+    /// Unified diff of the file, without the `diff --git` and `---`/`+++` header lines
+    pub diff: String,
+}
+
+pub type Diffs = Vec<Diff>;
+
+
+impl Diff {
+    /// Parse the diff into its hunks and lines, with the paths of the file.
+    pub fn parse(&self) -> Result<::diff::FileDiff> {
+        let mut file = ::diff::FileDiff::parse(&self.diff)
+            .chain_err(|| format!("cannot parse diff of '{}'", self.new_path))?;
+        file.old_path = if self.new_file { None } else { Some(self.old_path.clone()) };
+        file.new_path = if self.deleted_file { None } else { Some(self.new_path.clone()) };
+        Ok(file)
+    }
+}
+
+
+/// A merge request along with the changes it makes (`GET .../merge_requests/ID/changes`).
+#[derive(Debug)]
+pub struct MergeRequestChanges {
+    pub merge_request: MergeRequest,
+    pub changes: Diffs,
+}
+
+
+/// A version of the diff of a merge request: a new one is created each time the source branch
+/// is pushed to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffVersion {
+    pub id: i64,
+    pub head_commit_sha: String,
+    pub base_commit_sha: String,
+    pub start_commit_sha: String,
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    pub merge_request_id: i64,
+    /// `collected`, `overflow`, `without_files` or `empty`
+    pub state: String,
+    /// Number of files changed, e.g. `"1"`, or `"100+"` when the diff is too large
+    pub real_size: Option<String>,
+}
+
+pub type DiffVersions = Vec<DiffVersion>;
+
+
+/// A version of the diff of a merge request, with its commits and its diff.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffVersionDetails {
+    pub id: i64,
+    pub head_commit_sha: String,
+    pub base_commit_sha: String,
+    pub start_commit_sha: String,
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    pub merge_request_id: i64,
+    pub state: String,
+    pub real_size: Option<String>,
+    pub commits: Vec<::Commit>,
+    pub diffs: Diffs,
+}
+
+
+impl DiffVersion {
+    /// Position of a diff note on a line of `diff`, as of this version.
+    ///
+    /// See `::NotePosition` for which of `old_line` and `new_line` to give.
+    pub fn position(&self,
+                    diff: &Diff,
+                    old_line: Option<i64>,
+                    new_line: Option<i64>)
+                    -> ::NotePosition {
+        ::NotePosition {
+            base_sha: self.base_commit_sha.clone(),
+            start_sha: self.start_commit_sha.clone(),
+            head_sha: self.head_commit_sha.clone(),
+            position_type: ::PositionType::Text,
+            old_path: diff.old_path.clone(),
+            new_path: diff.new_path.clone(),
+            old_line: old_line,
+            new_line: new_line,
+        }
+    }
+}
+
+
+// GitLab returns the merge request's attributes and `changes` in the same object. Without
+// `#[serde(flatten)]`, go through a `serde_json::Value` to split them.
+impl Deserialize for MergeRequestChanges {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<MergeRequestChanges, D::Error>
+        where D: Deserializer
+    {
+        let mut value: serde_json::Value = Deserialize::deserialize(deserializer)?;
+        let changes = match value.as_object_mut().and_then(|object| object.remove("changes")) {
+            Some(changes) => changes,
+            None => return Err(D::Error::missing_field("changes")),
+        };
+
+        Ok(MergeRequestChanges {
+            merge_request: serde_json::from_value(value).map_err(D::Error::custom)?,
+            changes: serde_json::from_value(changes).map_err(D::Error::custom)?,
+        })
+    }
+}
+
+
+impl Serialize for MergeRequestChanges {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut value = serde_json::to_value(&self.merge_request).map_err(S::Error::custom)?;
+        let changes = serde_json::to_value(&self.changes).map_err(S::Error::custom)?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(String::from("changes"), changes);
+        }
+        value.serialize(serializer)
+    }
+}
+
+
+/// A thread of notes on a merge request. The notes of a discussion on a line of the diff are
+/// `DiffNote`s, with a position.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Discussion {
+    pub id: String,
+    /// `true` for a note which is not part of a thread
+    pub individual_note: bool,
+    pub notes: ::Notes,
+}
+
+pub type Discussions = Vec<Discussion>;
+
+
 #[derive(Debug, Clone)]
 pub struct MergeRequestsLister<'a> {
     gl: &'a ::GitLab,
@@ -144,6 +360,14 @@ impl<'a> Lister<MergeRequests> for MergeRequestsLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<MergeRequests>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -163,12 +387,61 @@ impl<'a> MergeRequestsLister<'a> {
     }
 
 
+    /// Return a lister for a single merge request. `merge_request_id` is the merge request's
+    /// `id` with API v3, its `iid` with API v4.
     pub fn single(self, merge_request_id: i64) -> single::MergeRequestLister<'a> {
         // assert_eq!(self, MergeRequestLister::new(self.gl));
         single::MergeRequestLister::new(self.gl, self.id, merge_request_id)
     }
 
 
+    pub fn create(self,
+                  source_branch: String,
+                  target_branch: String,
+                  title: String)
+                  -> create::MergeRequestCreator<'a> {
+        create::MergeRequestCreator::new(self.gl, self.id, source_branch, target_branch, title)
+    }
+
+
+    /// Return an editor for a merge request. `merge_request_id` is the merge request's `id`
+    /// with API v3, its `iid` with API v4.
+    pub fn edit(self, merge_request_id: i64) -> edit::MergeRequestEditor<'a> {
+        edit::MergeRequestEditor::new(self.gl, self.id, merge_request_id)
+    }
+
+
+    /// Return an accepter for a merge request. `merge_request_id` is the merge request's `id`
+    /// with API v3, its `iid` with API v4.
+    pub fn accept(self, merge_request_id: i64) -> accept::MergeRequestAccepter<'a> {
+        accept::MergeRequestAccepter::new(self.gl, self.id, merge_request_id)
+    }
+
+
+    /// Return a canceler of the merge of a merge request when its build succeeds.
+    /// `merge_request_id` is the merge request's `id` with API v3, its `iid` with API v4.
+    pub fn cancel_merge_when_build_succeeds(self,
+                                            merge_request_id: i64)
+                                            -> cancel_merge::MergeRequestMergeCanceler<'a> {
+        cancel_merge::MergeRequestMergeCanceler::new(self.gl, self.id, merge_request_id)
+    }
+
+
+    /// Return a lister for the notes of a merge request. `merge_request_id` is the merge
+    /// request's `id` with API v3, its `iid` with API v4.
+    pub fn notes(self, merge_request_id: i64) -> notes::MergeRequestNotesLister<'a> {
+        notes::MergeRequestNotesLister::new(self.gl, self.id, merge_request_id)
+    }
+
+
+    /// Return a lister for the discussions of a merge request (API v4 only).
+    pub fn discussions(self,
+                       merge_request_iid: i64)
+                       -> discussions::MergeRequestDiscussionsLister<'a> {
+        discussions::MergeRequestDiscussionsLister::new(self.gl, self.id, merge_request_iid)
+    }
+
+
     pub fn iid(&'a mut self, iid: Vec<i64>) -> &'a mut MergeRequestsLister {
         info!("Using 'idd' fails when there is more than one element!");
         if iid.len() > 1 {
@@ -192,6 +465,21 @@ impl<'a> MergeRequestsLister<'a> {
 }
 
 
+/// Turn an error caused by a non-success status code into the merge request specific kind `map`
+/// returns for that status code, so that it can be matched on with `e.kind()`. Other errors are
+/// chained with `context`.
+fn map_status_error<T, F, C>(result: Result<T>, map: F, context: C) -> Result<T>
+    where F: Fn(u16) -> Option<ErrorKind>,
+          C: FnOnce() -> String
+{
+    let kind = match result {
+        Ok(_) => return result,
+        Err(ref e) => e.kind().status().and_then(&map),
+    };
+    result.chain_err(|| kind.unwrap_or_else(|| ErrorKind::Msg(context())))
+}
+
+
 impl<'a> BuildQuery for MergeRequestsLister<'a> {
     fn build_query(&self) -> String {
 
@@ -219,14 +507,18 @@ impl<'a> BuildQuery for MergeRequestsLister<'a> {
             query.push_str(split_char);
             split_char = &amp_char;
 
-            if iid.len() == 1 {
+            let array_name = match self.gl.get_api_version() {
+                ::ApiVersion::V3 => "iid[]=",
+                ::ApiVersion::V4 => "iids[]=",
+            };
+            if iid.len() == 1 && self.gl.get_api_version() == ::ApiVersion::V3 {
                 query.push_str("iid=");
                 query.push_str(&iid[0].to_string());
             } else {
                 let mut array_split_char = &none_char;
                 for iid in iid {
                     query.push_str(array_split_char);
-                    query.push_str("iid[]=");
+                    query.push_str(array_name);
                     query.push_str(&iid.to_string());
                     array_split_char = &amp_char;
                 }
@@ -312,6 +604,22 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_iid_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let expected_string = format!("projects/{}/merge_requests?iids[]=456", TEST_PROJECT_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).iid(vec![456]).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = format!("projects/123/merge_requests?iids[]=456&iids[]=789");
+        let query = gl.merge_requests(TEST_PROJECT_ID).iid(vec![456, 789]).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
     #[test]
     fn build_query_state() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -390,4 +698,42 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn merge_request_helpers() {
+        use serde_json;
+
+        let merge_request: ::merge_requests::MergeRequest = serde_json::from_str(r#"{
+            "id": 1230, "iid": 45, "project_id": 123, "title": "Title", "description": "",
+            "state": "opened", "created_at": "2017-04-29T08:46:00Z",
+            "updated_at": "2017-04-29T08:46:00Z", "target_branch": "master",
+            "source_branch": "feature", "upvotes": 0, "downvotes": 0,
+            "author": {"name": "Administrator", "username": "root", "id": 1,
+                       "state": "active", "avatar_url": null, "web_url": null},
+            "assignee": null, "source_project_id": 123, "target_project_id": 123,
+            "labels": [], "work_in_progress": false, "milestone": null,
+            "merge_status": "can_be_merged", "user_notes_count": 1,
+            "web_url": "http://example.com/group/project/merge_requests/45"}"#)
+            .unwrap();
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        assert_eq!(merge_request.api_id(&gl), 1230);
+        assert_eq!(merge_request.single(&gl).build_query(), "projects/123/merge_requests/1230");
+        assert_eq!(merge_request.notes(&gl).build_query(),
+                   "projects/123/merge_requests/1230/notes");
+
+        let gl = gl.api_version(::ApiVersion::V4);
+        assert_eq!(merge_request.api_id(&gl), 45);
+        assert_eq!(merge_request.single(&gl).build_query(), "projects/123/merge_requests/45");
+        assert_eq!(merge_request.edit(&gl).build_query(), "projects/123/merge_requests/45");
+        assert_eq!(merge_request.accept(&gl).build_query(),
+                   "projects/123/merge_requests/45/merge");
+        assert_eq!(merge_request.cancel_merge_when_build_succeeds(&gl).build_query(),
+                   "projects/123/merge_requests/45/cancel_merge_when_pipeline_succeeds");
+        assert_eq!(merge_request.notes(&gl).build_query(),
+                   "projects/123/merge_requests/45/notes");
+        assert_eq!(merge_request.discussions(&gl).build_query(),
+                   "projects/123/merge_requests/45/discussions");
+    }
 }
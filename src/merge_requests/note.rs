@@ -0,0 +1,180 @@
+//! Single merge request note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#get-single-merge-request-note
+//!
+//! # Get single merge request note
+//!
+//! Returns a single note for a given merge request.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/notes/NOTE_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of a merge request (its `iid` with API v4)
+//! - `note_id` (required) - The ID of a note
+//!
+//! # Modify existing merge request note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#modify-existing-merge-request-note
+//!
+//! ```text
+//! PUT /projects/ID/merge_requests/MERGE_REQUEST_ID/notes/NOTE_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of a merge request (its `iid` with API v4)
+//! - `note_id` (required) - The ID of a note
+//! - `body` (required) - The content of a note
+//!
+//! # Delete a merge request note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#delete-a-merge-request-note
+//!
+//! ```text
+//! DELETE /projects/ID/merge_requests/MERGE_REQUEST_ID/notes/NOTE_ID
+//! ```
+//!
+//!
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestNoteLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a merge request (its `iid` with API v4)
+    mr_id: i64,
+    /// The ID of a note
+    note_id: i64,
+}
+
+
+impl<'a> MergeRequestNoteLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64, note_id: i64) -> MergeRequestNoteLister {
+        MergeRequestNoteLister {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            note_id: note_id,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the note.
+    pub fn list(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Return an editor replacing the body of the note
+    pub fn edit(self, body: String) -> MergeRequestNoteEditor<'a> {
+        MergeRequestNoteEditor::new(self.gl, self.id, self.mr_id, self.note_id, body)
+    }
+
+    /// Delete the note on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestNoteLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/notes/{}", self.id, self.mr_id, self.note_id)
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequestNoteEditorInternal {
+    /// The content of a note
+    body: String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestNoteEditor<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a merge request (its `iid` with API v4)
+    mr_id: i64,
+    /// The ID of a note
+    note_id: i64,
+    internal: MergeRequestNoteEditorInternal,
+}
+
+
+impl<'a> MergeRequestNoteEditor<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: i64,
+               mr_id: i64,
+               note_id: i64,
+               body: String)
+               -> MergeRequestNoteEditor {
+        MergeRequestNoteEditor {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            note_id: note_id,
+            internal: MergeRequestNoteEditorInternal { body: body },
+        }
+    }
+
+
+    /// Commit the editor: Update the note on GitLab and return it.
+    pub fn edit(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.internal).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestNoteEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/notes/{}", self.id, self.mr_id, self.note_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+    const TEST_NOTE_ID: i64 = 789;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/notes/{}",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID,
+                                      TEST_NOTE_ID);
+        let notes = || gl.merge_requests(TEST_PROJECT_ID).notes(TEST_MR_ID);
+        assert_eq!(notes().single(TEST_NOTE_ID).build_query(), expected_string);
+        assert_eq!(notes().edit(TEST_NOTE_ID, String::from("Body")).build_query(),
+                   expected_string);
+        assert_eq!(notes().single(TEST_NOTE_ID).edit(String::from("Body")).build_query(),
+                   expected_string);
+    }
+}
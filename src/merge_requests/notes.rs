@@ -0,0 +1,322 @@
+//! List merge request notes
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#list-all-merge-request-notes
+//!
+//! # List all merge request notes
+//!
+//! Gets a list of all notes for a single merge request.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/notes
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of a merge request (its `iid` with API v4)
+//! - `order_by` (optional) - Return notes ordered by `created_at` or `updated_at` fields. Default is `created_at`
+//! - `sort` (optional) - Return notes sorted in `asc` or `desc` order. Default is `desc`
+//!
+//! # Create new merge request note
+//!
+//! https://docs.gitlab.com/ce/api/notes.html#create-new-merge-request-note
+//!
+//! Creates a new note for a single merge request.
+//!
+//! ```text
+//! POST /projects/ID/merge_requests/MERGE_REQUEST_ID/notes
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of a merge request (its `iid` with API v4)
+//! - `body` (required) - The content of a note
+//! - `created_at` (optional) - Date time string, ISO 8601 formatted, e.g. `2016-03-11T03:45:40Z` (admins and project owners only)
+//!
+//! To comment on a line of the diff, start a discussion instead (see `discussions`).
+//!
+//!
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct MergeRequestNotesListerInternal {
+    /// Return notes ordered by. Default is `ListingOrderBy::CreatedAt`.
+    order_by: Option<::merge_requests::ListingOrderBy>,
+    /// Return notes sorted. Default is `::ListingSort::Desc`.
+    sort: Option<::ListingSort>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestNotesLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a merge request (its `iid` with API v4)
+    mr_id: i64,
+    internal: MergeRequestNotesListerInternal,
+}
+
+
+impl<'a> Lister<::Notes> for MergeRequestNotesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of notes.
+    fn list(&self) -> Result<::Notes> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of notes.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::Notes> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<::Notes>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> MergeRequestNotesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64) -> MergeRequestNotesLister {
+        MergeRequestNotesLister {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            internal: MergeRequestNotesListerInternal::default(),
+        }
+    }
+
+
+    /// Return a lister for a single note of the merge request
+    pub fn single(self, note_id: i64) -> ::merge_requests::note::MergeRequestNoteLister<'a> {
+        ::merge_requests::note::MergeRequestNoteLister::new(self.gl, self.id, self.mr_id, note_id)
+    }
+
+    /// Return a creator of a note on the merge request
+    pub fn create(self, body: String) -> MergeRequestNoteCreator<'a> {
+        MergeRequestNoteCreator::new(self.gl, self.id, self.mr_id, body)
+    }
+
+    /// Return an editor replacing the body of a note of the merge request
+    pub fn edit(self,
+                note_id: i64,
+                body: String)
+                -> ::merge_requests::note::MergeRequestNoteEditor<'a> {
+        ::merge_requests::note::MergeRequestNoteEditor::new(self.gl,
+                                                            self.id,
+                                                            self.mr_id,
+                                                            note_id,
+                                                            body)
+    }
+
+    /// Delete a note of the merge request on GitLab.
+    pub fn delete(self, note_id: i64) -> Result<()> {
+        self.single(note_id).delete()
+    }
+
+
+    pub fn order_by(&'a mut self,
+                    order_by: ::merge_requests::ListingOrderBy)
+                    -> &'a mut MergeRequestNotesLister {
+        self.internal.order_by = Some(order_by);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut MergeRequestNotesLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestNotesLister<'a> {
+    fn build_query(&self) -> String {
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        ::join_query(&format!("projects/{}/merge_requests/{}/notes", self.id, self.mr_id),
+                     &[&encoded])
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequestNoteCreatorInternal {
+    /// The content of a note
+    body: String,
+    /// Date time string, ISO 8601 formatted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MergeRequestNoteCreator<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of a project
+    id: i64,
+    /// The ID of a merge request (its `iid` with API v4)
+    mr_id: i64,
+    internal: MergeRequestNoteCreatorInternal,
+}
+
+
+impl<'a> MergeRequestNoteCreator<'a> {
+    pub fn new(gl: &'a ::GitLab, id: i64, mr_id: i64, body: String) -> MergeRequestNoteCreator {
+        MergeRequestNoteCreator {
+            gl: gl,
+            id: id,
+            mr_id: mr_id,
+            internal: MergeRequestNoteCreatorInternal {
+                body: body,
+                created_at: None,
+            },
+        }
+    }
+
+
+    /// Creation date, ISO 8601 formatted (e.g. `2016-03-11T03:45:40Z`). Requires admin or
+    /// project owner rights.
+    pub fn created_at(&'a mut self, created_at: String) -> &'a mut MergeRequestNoteCreator {
+        self.internal.created_at = Some(created_at);
+        self
+    }
+
+
+    /// Commit the creator: Create the note on GitLab and return it.
+    pub fn create(&self) -> Result<::Note> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.internal).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for MergeRequestNoteCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/merge_requests/{}/notes", self.id, self.mr_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_MR_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/merge_requests/{}/notes",
+                                      TEST_PROJECT_ID,
+                                      TEST_MR_ID);
+        let query = gl.merge_requests(TEST_PROJECT_ID).notes(TEST_MR_ID).build_query();
+        assert_eq!(query, expected_string);
+
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .notes(TEST_MR_ID)
+            .create(String::from("Body"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.merge_requests(TEST_PROJECT_ID)
+            .notes(TEST_MR_ID)
+            .order_by(::merge_requests::ListingOrderBy::UpdatedAt)
+            .sort(::ListingSort::Asc)
+            .build_query();
+        assert_eq!(query,
+                   format!("projects/{}/merge_requests/{}/notes?order_by=updated_at&sort=asc",
+                           TEST_PROJECT_ID,
+                           TEST_MR_ID));
+    }
+
+
+    #[test]
+    fn body_create() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.merge_requests(TEST_PROJECT_ID)
+            .notes(TEST_MR_ID)
+            .create(String::from("Hi"));
+        assert_eq!(serde_json::to_string(&creator.internal).unwrap(), r#"{"body":"Hi"}"#);
+
+        let body = serde_json::to_string(&gl.merge_requests(TEST_PROJECT_ID)
+                .notes(TEST_MR_ID)
+                .create(String::from("Hi"))
+                .created_at(String::from("2016-03-11T03:45:40Z"))
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"body":"Hi","created_at":"2016-03-11T03:45:40Z"}"#);
+    }
+
+
+    #[test]
+    fn notes_through_transport() {
+        use Lister;
+        use transport::{Method, MockTransport, Response};
+
+        let note_json = r#"{"id": 302, "body": "closed", "attachment": null,
+            "author": {"id": 1, "username": "pipin", "email": "admin@example.com",
+                       "name": "Pip", "state": "active", "avatar_url": null,
+                       "web_url": "http://localhost/pipin"},
+            "created_at": "2013-10-02T09:22:45Z", "updated_at": "2013-10-02T10:22:45Z",
+            "system": true, "noteable_id": 377, "noteable_type": "MergeRequest"}"#;
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &format!("[{}]", note_json)));
+        mock.push_response(Response::new(201, note_json));
+        mock.push_response(Response::new(200, note_json));
+        mock.push_response(Response::new(200, note_json));
+        mock.push_response(Response::new(204, ""));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let notes = || gl.merge_requests(TEST_PROJECT_ID).notes(TEST_MR_ID);
+
+        let listed = notes().list().unwrap();
+        assert_eq!(listed[0].id, 302);
+        assert!(listed[0].system);
+        assert_eq!(listed[0].author.username, "pipin");
+
+        notes().create(String::from("Hello")).create().unwrap();
+        notes().single(302).list().unwrap();
+        notes().edit(302, String::from("Bye")).edit().unwrap();
+        notes().delete(302).unwrap();
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|request| request.method).collect();
+        assert_eq!(methods,
+                   vec![Method::Get, Method::Post, Method::Get, Method::Put, Method::Delete]);
+        assert_eq!(requests[1].body.as_ref().unwrap(), r#"{"body":"Hello"}"#);
+        assert_eq!(requests[3].body.as_ref().unwrap(), r#"{"body":"Bye"}"#);
+        assert_eq!(requests[4].url,
+                   "https://localhost/api/v3/projects/123/merge_requests/456/notes/302");
+    }
+}
@@ -13,7 +13,45 @@
 //! Parameters:
 //!
 //! - `id` (required) - The ID of a project
-//! - `merge_request_id` (required) - The ID of MR
+//! - `merge_request_id` (required) - The ID of MR (its `iid` with API v4)
+//!
+//! # Get single MR changes
+//!
+//! Shows information about the merge request including its files and changes.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/changes
+//! ```
+//!
+//! # Get single MR commits
+//!
+//! Get a list of merge request commits.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/commits
+//! ```
+//!
+//! # Get MR diff versions
+//!
+//! Get a list of merge request diff versions.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/versions
+//! ```
+//!
+//! # Get a single MR diff version
+//!
+//! Get a single merge request diff version, with its commits and diffs.
+//!
+//! ```text
+//! GET /projects/ID/merge_requests/MERGE_REQUEST_ID/versions/VERSION_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! - `id` (required) - The ID of a project
+//! - `merge_request_id` (required) - The ID of MR (its `iid` with API v4)
+//! - `version_id` (required) - The ID of the merge request diff version
 //!
 //!
 
@@ -31,6 +69,7 @@ use ::errors::*;
 pub struct MergeRequestLister<'a> {
     gl: &'a ::GitLab,
     id: i64,
+    /// The ID of the merge request (its `iid` with API v4)
     mr_id: i64,
 }
 
@@ -52,6 +91,36 @@ impl<'a> MergeRequestLister<'a> {
 
         self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Query GitLab and return the merge request along with the changes of its files.
+    pub fn changes(&self) -> Result<::merge_requests::MergeRequestChanges> {
+        self.get_sub_resource("changes")
+    }
+
+    /// Query GitLab and return the commits of the merge request.
+    pub fn commits(&self) -> Result<Vec<::Commit>> {
+        self.get_sub_resource("commits")
+    }
+
+    /// Query GitLab and return the diff versions of the merge request, newest first.
+    pub fn versions(&self) -> Result<::merge_requests::DiffVersions> {
+        self.get_sub_resource("versions")
+    }
+
+    /// Query GitLab and return a diff version of the merge request, with its commits and diffs.
+    pub fn version(&self, version_id: i64) -> Result<::merge_requests::DiffVersionDetails> {
+        self.get_sub_resource(&format!("versions/{}", version_id))
+    }
+
+
+    fn get_sub_resource<T>(&self, path: &str) -> Result<T>
+        where T: ::serde::Deserialize
+    {
+        let query = format!("{}/{}", self.build_query(), path);
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -88,4 +157,95 @@ mod tests {
         let query = gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID).build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn changes_commits_and_versions_through_transport() {
+        use serde_json;
+        use transport::{Method, MockTransport, Response};
+
+        let merge_request_json = r#""id": 21, "iid": 1, "project_id": 4, "title": "Blanditiis",
+            "description": "", "state": "opened", "created_at": "2015-02-02T19:49:26.013Z",
+            "updated_at": "2015-02-02T20:08:49.959Z", "target_branch": "secret_token",
+            "source_branch": "version-1-9", "upvotes": 0, "downvotes": 0,
+            "author": {"name": "Chad Hamill", "username": "jarrett", "id": 5,
+                       "state": "active", "avatar_url": null, "web_url": null},
+            "assignee": null, "source_project_id": 4, "target_project_id": 4, "labels": [],
+            "work_in_progress": false, "milestone": null, "merge_status": "can_be_merged",
+            "user_notes_count": 0, "web_url": "http://example.com/group/project/merge_requests/1""#;
+        let diff_json = r#"{"old_path": "VERSION", "new_path": "VERSION",
+            "a_mode": "100644", "b_mode": "100644", "diff": "@@ -1 +1 @@\n-1.9.7\n+1.9.8\n",
+            "new_file": false, "renamed_file": false, "deleted_file": false}"#;
+        let commit_json = r#"{"id": "ed899a2f4b50b4370feeea94676502b42383c746",
+            "short_id": "ed899a2f4b5", "title": "Replace sanitize with escape once",
+            "author_name": "Dmitriy Zaporozhets", "author_email": "dzaporozhets@sphereconsult.com",
+            "created_at": "2012-09-20T11:50:22+03:00",
+            "message": "Replace sanitize with escape once"}"#;
+        let version_json = r#""id": 110,
+            "head_commit_sha": "33e2ee8579fda5bc36accc9c6fbd0b4fefda9e30",
+            "base_commit_sha": "eeb57dffe83deb686a60a71c16c32f71046868fd",
+            "start_commit_sha": "eeb57dffe83deb686a60a71c16c32f71046868fd",
+            "created_at": "2016-07-26T14:44:48.926Z", "merge_request_id": 105,
+            "state": "collected", "real_size": "1""#;
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200,
+                                         &format!("{{{}, \"changes\": [{}]}}",
+                                                  merge_request_json,
+                                                  diff_json)));
+        mock.push_response(Response::new(200, &format!("[{}]", commit_json)));
+        mock.push_response(Response::new(200, &format!("[{{{}}}]", version_json)));
+        mock.push_response(Response::new(200,
+                                         &format!("{{{}, \"commits\": [{}], \"diffs\": [{}]}}",
+                                                  version_json,
+                                                  commit_json,
+                                                  diff_json)));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let merge_request = || gl.merge_requests(TEST_PROJECT_ID).single(TEST_MR_ID);
+
+        let changes = merge_request().changes().unwrap();
+        assert_eq!(changes.merge_request.iid, 1);
+        assert_eq!(changes.changes[0].new_path, "VERSION");
+        assert_eq!(changes.changes[0].a_mode, Some(String::from("100644")));
+        assert!(!changes.changes[0].new_file);
+        assert_eq!(changes.changes[0].diff, "@@ -1 +1 @@\n-1.9.7\n+1.9.8\n");
+        let file_diff = changes.changes[0].parse().unwrap();
+        assert_eq!(file_diff.old_path, Some(String::from("VERSION")));
+        assert_eq!(file_diff.line_at_new(1).unwrap().content, "1.9.8");
+        let reserialized = serde_json::to_value(&changes).unwrap();
+        assert_eq!(reserialized["changes"][0]["old_path"], "VERSION");
+        assert_eq!(reserialized["iid"], 1);
+
+        let commits = merge_request().commits().unwrap();
+        assert_eq!(commits[0].short_id, Some(String::from("ed899a2f4b5")));
+
+        let versions = merge_request().versions().unwrap();
+        assert_eq!(versions[0].id, 110);
+        assert_eq!(versions[0].real_size, Some(String::from("1")));
+        let position = versions[0].position(&changes.changes[0], None, Some(1));
+        assert_eq!(position.head_sha, "33e2ee8579fda5bc36accc9c6fbd0b4fefda9e30");
+        assert_eq!(position.start_sha, "eeb57dffe83deb686a60a71c16c32f71046868fd");
+        assert_eq!(position.new_path, "VERSION");
+        assert_eq!(position.new_line, Some(1));
+
+        let version = merge_request().version(110).unwrap();
+        assert_eq!(version.commits.len(), 1);
+        assert_eq!(version.diffs[0].old_path, "VERSION");
+
+        let requests: Vec<_> = mock.requests()
+            .into_iter()
+            .map(|request| (request.method, request.url))
+            .collect();
+        let url = |path: &str| {
+            format!("https://localhost/api/v3/projects/123/merge_requests/456/{}", path)
+        };
+        assert_eq!(requests,
+                   vec![(Method::Get, url("changes")),
+                        (Method::Get, url("commits")),
+                        (Method::Get, url("versions")),
+                        (Method::Get, url("versions/110"))]);
+    }
 }
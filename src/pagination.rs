@@ -0,0 +1,456 @@
+//! Pagination
+//!
+//! https://docs.gitlab.com/ce/api/README.html#pagination
+//!
+//! GitLab returns lists of items one page at a time (20 items per page by default, 100 at most).
+//! `ListerIter`, obtained through `Lister::iter()`, walks over all the items of a lister,
+//! fetching the next page from the server only once the current one has been consumed.
+//!
+//! The pagination information sent back by GitLab in the response headers is available through
+//! `Lister::list_page()`, which returns a `Page`:
+//!
+//! | Header | Description |
+//! | ------ | ----------- |
+//! | `X-Total` | The total number of items |
+//! | `X-Total-Pages` | The total number of pages |
+//! | `X-Per-Page` | The number of items per page |
+//! | `X-Page` | The index of the current page (starting at 1) |
+//! | `X-Next-Page` | The index of the next page |
+//! | `X-Prev-Page` | The index of the previous page |
+//! | `Link` | Links to the `first`, `prev`, `next` and `last` pages |
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gitlab_api::GitLab;
+//! use gitlab_api::Lister;
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+//!
+//! let page = gl.projects().list_page(1, 20).unwrap();
+//! println!("{} projects on {:?} pages", page.items.len(), page.pagination.total_pages);
+//! ```
+//!
+//! ```no_run
+//! use gitlab_api::GitLab;
+//! use gitlab_api::Lister;
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+//!
+//! for project in gl.projects().owned().iter() {
+//!     let project = project.unwrap();
+//!     println!("{}", project.path_with_namespace);
+//! }
+//! ```
+
+
+use std;
+
+use Lister;
+use transport::Response;
+
+use ::errors::*;
+
+
+/// Number of items requested per page when none is specified.
+pub const DEFAULT_PER_PAGE: u16 = 20;
+
+/// Maximum number of items GitLab returns per page.
+pub const MAX_PER_PAGE: u16 = 100;
+
+
+/// Bound a number of items per page to what GitLab accepts: between 1 and `MAX_PER_PAGE`.
+pub fn clamp_per_page(per_page: u16) -> u16 {
+    std::cmp::max(1, std::cmp::min(per_page, MAX_PER_PAGE))
+}
+
+
+/// A page of items, with the pagination information sent by the server.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: T,
+    pub pagination: Pagination,
+}
+
+
+impl<T> Page<Vec<T>> {
+    /// Is this the last page, `per_page` items having been requested?
+    ///
+    /// The pagination headers are trusted when the server sent them; otherwise a page holding
+    /// less than `per_page` items is the last one.
+    pub fn is_last(&self, per_page: u16) -> bool {
+        match self.pagination.has_next_page() {
+            Some(has_next_page) => !has_next_page,
+            None => self.items.len() < per_page as usize,
+        }
+    }
+}
+
+
+/// Pagination information, parsed from the response headers.
+///
+/// Any header missing from the response (or empty, as `X-Next-Page` on the last page) is `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pagination {
+    /// `X-Total`
+    pub total: Option<u64>,
+    /// `X-Total-Pages`
+    pub total_pages: Option<u16>,
+    /// `X-Per-Page`
+    pub per_page: Option<u16>,
+    /// `X-Page`
+    pub page: Option<u16>,
+    /// `X-Next-Page`
+    pub next_page: Option<u16>,
+    /// `X-Prev-Page`
+    pub prev_page: Option<u16>,
+    /// `Link`
+    pub links: Links,
+}
+
+
+/// URLs of other pages, parsed from the `Link` header.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Links {
+    pub first: Option<String>,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+    pub last: Option<String>,
+}
+
+
+impl Pagination {
+    pub fn from_response(response: &Response) -> Pagination {
+        Pagination {
+            total: parse_header(response, "X-Total"),
+            total_pages: parse_header(response, "X-Total-Pages"),
+            per_page: parse_header(response, "X-Per-Page"),
+            page: parse_header(response, "X-Page"),
+            next_page: parse_header(response, "X-Next-Page"),
+            prev_page: parse_header(response, "X-Prev-Page"),
+            links: response.header("Link").map(parse_link_header).unwrap_or_else(Links::default),
+        }
+    }
+
+    /// Is there a page after this one?
+    ///
+    /// Returns `None` if the server did not send any pagination header (`X-Page` and
+    /// `X-Next-Page`), in which case the number of items on the page is the only hint left.
+    pub fn has_next_page(&self) -> Option<bool> {
+        match (self.page, self.next_page) {
+            (None, None) => None,
+            (_, next_page) => Some(next_page.is_some()),
+        }
+    }
+}
+
+
+fn parse_header<T>(response: &Response, name: &str) -> Option<T>
+    where T: std::str::FromStr
+{
+    response.header(name).and_then(|value| value.trim().parse().ok())
+}
+
+
+/// Parse a `Link` header, as described by RFC 5988.
+///
+/// Example: `<https://gitlab.example.com/api/v3/projects?page=2&per_page=20>; rel="next"`
+fn parse_link_header(header: &str) -> Links {
+    let mut links = Links::default();
+
+    for link in header.split(',') {
+        let mut parts = link.split(';');
+        let url = match parts.next() {
+            Some(url) => url.trim().trim_left_matches('<').trim_right_matches('>').to_string(),
+            None => continue,
+        };
+
+        for param in parts {
+            let param = param.trim();
+            if !param.starts_with("rel=") {
+                continue;
+            }
+            match param["rel=".len()..].trim_matches('"') {
+                "first" => links.first = Some(url.clone()),
+                "prev" => links.prev = Some(url.clone()),
+                "next" => links.next = Some(url.clone()),
+                "last" => links.last = Some(url.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    links
+}
+
+
+/// Iterator over all items of a `Lister`, fetching pages lazily.
+///
+/// Iteration stops after the last page (see `Page::is_last()`): the server reports no next page
+/// (empty `X-Next-Page` header) or, without pagination headers, the page holds less than
+/// `per_page` items.
+/// If fetching a page fails, the error is returned and the iteration stops.
+pub struct ListerIter<'a, L: 'a, T> {
+    lister: &'a L,
+    page: u16,
+    per_page: u16,
+    items: std::vec::IntoIter<T>,
+    done: bool,
+}
+
+
+impl<'a, L, T> ListerIter<'a, L, T>
+    where L: Lister<Vec<T>>
+{
+    pub fn new(lister: &'a L) -> ListerIter<'a, L, T> {
+        ListerIter {
+            lister: lister,
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+            items: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    /// Number of items to request for each page (bounded between 1 and 100).
+    pub fn per_page(mut self, per_page: u16) -> Self {
+        self.per_page = clamp_per_page(per_page);
+        self
+    }
+}
+
+
+impl<'a, L, T> Iterator for ListerIter<'a, L, T>
+    where L: Lister<Vec<T>>
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            match self.lister.list_page(self.page, self.per_page) {
+                Ok(page) => {
+                    self.done = page.is_last(self.per_page);
+                    self.page += 1;
+                    self.items = page.items.into_iter();
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e).chain_err(|| format!("cannot get page {}", self.page)));
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use GitLab;
+    use Lister;
+    use fixtures::groups_page;
+    use pagination::*;
+    use transport::{MockTransport, Response};
+
+
+    #[test]
+    fn iter_stops_on_short_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2)));
+        mock.push_response(Response::new(200, &groups_page(3, 2)));
+        mock.push_response(Response::new(200, &groups_page(5, 1)));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        let ids: Vec<i64> = lister.iter().per_page(2).map(|group| group.unwrap().id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].url.ends_with("page=1&per_page=2"));
+        assert!(requests[1].url.ends_with("page=2&per_page=2"));
+        assert!(requests[2].url.ends_with("page=3&per_page=2"));
+    }
+
+
+    #[test]
+    fn iter_stops_on_empty_next_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2))
+            .with_header("X-Page", "1")
+            .with_header("X-Next-Page", "2"));
+        mock.push_response(Response::new(200, &groups_page(3, 2))
+            .with_header("X-Page", "2")
+            .with_header("X-Next-Page", ""));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        let ids: Vec<i64> = lister.iter().per_page(2).map(|group| group.unwrap().id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+
+    #[test]
+    fn iter_trusts_next_page_over_short_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 1))
+            .with_header("X-Page", "1")
+            .with_header("X-Next-Page", "2"));
+        mock.push_response(Response::new(200, &groups_page(2, 2))
+            .with_header("X-Page", "2")
+            .with_header("X-Next-Page", ""));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        let ids: Vec<i64> = lister.iter().per_page(2).map(|group| group.unwrap().id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+
+    #[test]
+    fn iter_clamps_per_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 1))
+            .with_header("X-Page", "1")
+            .with_header("X-Next-Page", ""));
+        mock.push_response(Response::new(200, "[]"));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        assert_eq!(lister.iter().per_page(0).count(), 1);
+        assert_eq!(lister.iter().per_page(500).count(), 0);
+
+        let requests = mock.requests();
+        assert!(requests[0].url.ends_with("page=1&per_page=1"));
+        assert!(requests[1].url.ends_with("page=1&per_page=100"));
+    }
+
+
+    #[test]
+    fn iter_is_lazy() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 20)));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        let first = lister.iter().next().unwrap().unwrap();
+        assert_eq!(first.id, 1);
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+
+    #[test]
+    fn iter_stops_on_error() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 20)));
+        mock.push_response(Response::new(500, ""));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let lister = gl.groups();
+        let results: Vec<_> = lister.iter().collect();
+        assert_eq!(results.len(), 21);
+        assert!(results[..20].iter().all(|result| result.is_ok()));
+        assert!(results[20].is_err());
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+
+    #[test]
+    fn pagination_from_headers() {
+        let response = Response::new(200, "[]")
+            .with_header("X-Total", "47")
+            .with_header("X-Total-Pages", "3")
+            .with_header("X-Per-Page", "20")
+            .with_header("X-Page", "2")
+            .with_header("X-Next-Page", "3")
+            .with_header("X-Prev-Page", "1")
+            .with_header("Link",
+                         "<https://gitlab.example.com/api/v3/projects?page=1&per_page=20>; \
+                          rel=\"prev\", \
+                          <https://gitlab.example.com/api/v3/projects?page=3&per_page=20>; \
+                          rel=\"next\", \
+                          <https://gitlab.example.com/api/v3/projects?page=1&per_page=20>; \
+                          rel=\"first\", \
+                          <https://gitlab.example.com/api/v3/projects?page=3&per_page=20>; \
+                          rel=\"last\"");
+
+        let pagination = Pagination::from_response(&response);
+        assert_eq!(pagination.total, Some(47));
+        assert_eq!(pagination.total_pages, Some(3));
+        assert_eq!(pagination.per_page, Some(20));
+        assert_eq!(pagination.page, Some(2));
+        assert_eq!(pagination.next_page, Some(3));
+        assert_eq!(pagination.prev_page, Some(1));
+        assert_eq!(pagination.has_next_page(), Some(true));
+        let page_1 = "https://gitlab.example.com/api/v3/projects?page=1&per_page=20";
+        let page_3 = "https://gitlab.example.com/api/v3/projects?page=3&per_page=20";
+        assert_eq!(pagination.links,
+                   Links {
+                       first: Some(String::from(page_1)),
+                       prev: Some(String::from(page_1)),
+                       next: Some(String::from(page_3)),
+                       last: Some(String::from(page_3)),
+                   });
+    }
+
+
+    #[test]
+    fn pagination_last_page() {
+        let response = Response::new(200, "[]")
+            .with_header("X-Page", "3")
+            .with_header("X-Next-Page", "")
+            .with_header("X-Prev-Page", "2");
+
+        let pagination = Pagination::from_response(&response);
+        assert_eq!(pagination.page, Some(3));
+        assert_eq!(pagination.next_page, None);
+        assert_eq!(pagination.prev_page, Some(2));
+        assert_eq!(pagination.has_next_page(), Some(false));
+    }
+
+
+    #[test]
+    fn pagination_without_headers() {
+        let pagination = Pagination::from_response(&Response::new(200, "[]"));
+        assert_eq!(pagination, Pagination::default());
+        assert_eq!(pagination.has_next_page(), None);
+    }
+
+
+    #[test]
+    fn list_page() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &groups_page(1, 2))
+            .with_header("X-Total", "5")
+            .with_header("X-Total-Pages", "3"));
+        let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let page = gl.groups().list_page(1, 2).unwrap();
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.pagination.total, Some(5));
+        assert_eq!(page.pagination.total_pages, Some(3));
+    }
+}
@@ -10,6 +10,8 @@
 //! GET /projects/all
 //! ```
 //!
+//! With API v4, this is `GET /projects`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -24,6 +26,7 @@
 use serde_urlencoded;
 
 use BuildQuery;
+use Lister;
 
 use ::errors::*;
 
@@ -34,6 +37,36 @@ pub struct ProjectsLister<'a> {
     internal: ::projects::AllProjectListerInternal,
 }
 
+impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list(&self) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> ProjectsLister<'a> {
     pub fn new(gl: &'a ::GitLab) -> ProjectsLister {
         ProjectsLister {
@@ -73,13 +106,12 @@ impl<'a> ProjectsLister<'a> {
         self
     }
 
+
     /// Commit the lister: Query GitLab and return a list of projects.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<::projects::Projects> {
-        // let query = serde_urlencoded::to_string(&self);
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
@@ -88,11 +120,11 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = String::from("projects/all");
-        if !encoded.is_empty() {
-            query.push_str("?");
-            query.push_str(&encoded);
-        }
+        let path = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => "projects/all",
+            ::ApiVersion::V4 => "projects",
+        };
+        let query = ::join_query(path, &[&encoded]);
         debug!("query: {}", query);
 
         query
@@ -121,6 +153,17 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        assert_eq!(gl.projects().all().build_query(), "projects");
+        assert_eq!(gl.projects().all().archived(true).build_query(), "projects?archived=true");
+    }
+
+
     #[test]
     fn build_query_archived() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -0,0 +1,325 @@
+//! Create project
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#create-project
+//!
+//! # Create project
+//!
+//! Creates a new project owned by the authenticated user.
+//!
+//! If the operation is successful, a status code of `201` and the newly-created project is
+//! returned.
+//!
+//! ```text
+//! POST /projects
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `name` | string | yes | The name of the new project |
+//! | `path` | string | no | Custom repository name for new project. By default generated based on name |
+//! | `namespace_id` | integer | no | Namespace for the new project (defaults to the current user's namespace) |
+//! | `description` | string | no | Short project description |
+//! | `issues_enabled` | boolean | no | Enable issues for this project |
+//! | `merge_requests_enabled` | boolean | no | Enable merge requests for this project |
+//! | `jobs_enabled` | boolean | no | Enable jobs for this project |
+//! | `wiki_enabled` | boolean | no | Enable wiki for this project |
+//! | `snippets_enabled` | boolean | no | Enable snippets for this project |
+//! | `container_registry_enabled` | boolean | no | Enable container registry for this project |
+//! | `shared_runners_enabled` | boolean | no | Enable shared runners for this project |
+//! | `visibility` | string | no | `private`, `internal` or `public` |
+//! | `import_url` | string | no | URL to import repository from |
+//! | `lfs_enabled` | boolean | no | Enable LFS |
+//! | `request_access_enabled` | boolean | no | Allow users to request member access |
+//! | `only_allow_merge_if_pipeline_succeeds` | boolean | no | Set whether merge requests can only be merged with successful jobs |
+//! | `only_allow_merge_if_all_discussions_are_resolved` | boolean | no | Set whether merge requests can only be merged when all the discussions are resolved |
+//!
+//! With API v3, `visibility` is sent as `visibility_level` (`0`, `10` or `20`), `jobs_enabled` as
+//! `builds_enabled` and `only_allow_merge_if_pipeline_succeeds` as
+//! `only_allow_merge_if_build_succeeds`.
+//!
+
+
+use serde_json;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone, Serialize)]
+struct ProjectCreatorInternal {
+    /// The name of the new project
+    name: String,
+    /// Custom repository name for new project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// Namespace for the new project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace_id: Option<i64>,
+    /// Short project description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Enable issues for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issues_enabled: Option<bool>,
+    /// Enable merge requests for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_requests_enabled: Option<bool>,
+    /// Enable jobs (builds with API v3) for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jobs_enabled: Option<bool>,
+    /// Enable wiki for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wiki_enabled: Option<bool>,
+    /// Enable snippets for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippets_enabled: Option<bool>,
+    /// Enable container registry for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container_registry_enabled: Option<bool>,
+    /// Enable shared runners for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_runners_enabled: Option<bool>,
+    /// Visibility of the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<::ListingVisibility>,
+    /// URL to import repository from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    import_url: Option<String>,
+    /// Enable LFS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lfs_enabled: Option<bool>,
+    /// Allow users to request member access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_access_enabled: Option<bool>,
+    /// Merge requests can only be merged with successful jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_allow_merge_if_pipeline_succeeds: Option<bool>,
+    /// Merge requests can only be merged when all the discussions are resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_allow_merge_if_all_discussions_are_resolved: Option<bool>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectCreator<'a> {
+    gl: &'a ::GitLab,
+    internal: ProjectCreatorInternal,
+}
+
+
+impl<'a> ProjectCreator<'a> {
+    pub fn new(gl: &'a ::GitLab, name: String) -> ProjectCreator {
+        ProjectCreator {
+            gl: gl,
+            internal: ProjectCreatorInternal {
+                name: name,
+                path: None,
+                namespace_id: None,
+                description: None,
+                issues_enabled: None,
+                merge_requests_enabled: None,
+                jobs_enabled: None,
+                wiki_enabled: None,
+                snippets_enabled: None,
+                container_registry_enabled: None,
+                shared_runners_enabled: None,
+                visibility: None,
+                import_url: None,
+                lfs_enabled: None,
+                request_access_enabled: None,
+                only_allow_merge_if_pipeline_succeeds: None,
+                only_allow_merge_if_all_discussions_are_resolved: None,
+            },
+        }
+    }
+
+
+    pub fn path(&'a mut self, path: String) -> &'a mut ProjectCreator {
+        self.internal.path = Some(path);
+        self
+    }
+
+    pub fn namespace_id(&'a mut self, namespace_id: i64) -> &'a mut ProjectCreator {
+        self.internal.namespace_id = Some(namespace_id);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut ProjectCreator {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn issues_enabled(&'a mut self, issues_enabled: bool) -> &'a mut ProjectCreator {
+        self.internal.issues_enabled = Some(issues_enabled);
+        self
+    }
+
+    pub fn merge_requests_enabled(&'a mut self,
+                                  merge_requests_enabled: bool)
+                                  -> &'a mut ProjectCreator {
+        self.internal.merge_requests_enabled = Some(merge_requests_enabled);
+        self
+    }
+
+    /// Enable jobs (builds with API v3).
+    pub fn jobs_enabled(&'a mut self, jobs_enabled: bool) -> &'a mut ProjectCreator {
+        self.internal.jobs_enabled = Some(jobs_enabled);
+        self
+    }
+
+    pub fn wiki_enabled(&'a mut self, wiki_enabled: bool) -> &'a mut ProjectCreator {
+        self.internal.wiki_enabled = Some(wiki_enabled);
+        self
+    }
+
+    pub fn snippets_enabled(&'a mut self, snippets_enabled: bool) -> &'a mut ProjectCreator {
+        self.internal.snippets_enabled = Some(snippets_enabled);
+        self
+    }
+
+    pub fn container_registry_enabled(&'a mut self,
+                                      container_registry_enabled: bool)
+                                      -> &'a mut ProjectCreator {
+        self.internal.container_registry_enabled = Some(container_registry_enabled);
+        self
+    }
+
+    pub fn shared_runners_enabled(&'a mut self,
+                                  shared_runners_enabled: bool)
+                                  -> &'a mut ProjectCreator {
+        self.internal.shared_runners_enabled = Some(shared_runners_enabled);
+        self
+    }
+
+    pub fn visibility(&'a mut self, visibility: ::ListingVisibility) -> &'a mut ProjectCreator {
+        self.internal.visibility = Some(visibility);
+        self
+    }
+
+    pub fn import_url(&'a mut self, import_url: String) -> &'a mut ProjectCreator {
+        self.internal.import_url = Some(import_url);
+        self
+    }
+
+    pub fn lfs_enabled(&'a mut self, lfs_enabled: bool) -> &'a mut ProjectCreator {
+        self.internal.lfs_enabled = Some(lfs_enabled);
+        self
+    }
+
+    pub fn request_access_enabled(&'a mut self,
+                                  request_access_enabled: bool)
+                                  -> &'a mut ProjectCreator {
+        self.internal.request_access_enabled = Some(request_access_enabled);
+        self
+    }
+
+    /// Merge requests can only be merged with successful jobs (builds with API v3).
+    pub fn only_allow_merge_if_pipeline_succeeds(&'a mut self,
+                                                 only_allow: bool)
+                                                 -> &'a mut ProjectCreator {
+        self.internal.only_allow_merge_if_pipeline_succeeds = Some(only_allow);
+        self
+    }
+
+    pub fn only_allow_merge_if_all_discussions_are_resolved(&'a mut self,
+                                                            only_allow: bool)
+                                                            -> &'a mut ProjectCreator {
+        self.internal.only_allow_merge_if_all_discussions_are_resolved = Some(only_allow);
+        self
+    }
+
+
+    /// Body of the request, with the attributes' names of the API version used.
+    fn body(&self) -> serde_json::Value {
+        let body = serde_json::to_value(&self.internal).unwrap();
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => ::projects::v3_attributes(body),
+            ::ApiVersion::V4 => body,
+        }
+    }
+
+
+    /// Commit the creator: Create the project on GitLab and return it.
+    pub fn create(&self) -> Result<::projects::Project> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.body()).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectCreator<'a> {
+    fn build_query(&self) -> String {
+        String::from("projects")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects().create(String::from("Name")).build_query();
+        assert_eq!(query, "projects");
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let creator = gl.projects().create(String::from("Name"));
+        let body = serde_json::to_string(&creator.body()).unwrap();
+        assert_eq!(body, r#"{"name":"Name"}"#);
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let body = serde_json::to_string(&gl.projects()
+                .create(String::from("Name"))
+                .path(String::from("name"))
+                .namespace_id(12)
+                .description(String::from("Description"))
+                .issues_enabled(true)
+                .jobs_enabled(false)
+                .visibility(::ListingVisibility::Internal)
+                .only_allow_merge_if_pipeline_succeeds(true)
+                .body())
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"description":"Description","issues_enabled":true,"#,
+                           r#""jobs_enabled":false,"name":"Name","namespace_id":12,"#,
+                           r#""only_allow_merge_if_pipeline_succeeds":true,"path":"name","#,
+                           r#""visibility":"internal"}"#));
+    }
+
+
+    #[test]
+    fn body_v3() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.projects()
+                .create(String::from("Name"))
+                .jobs_enabled(false)
+                .visibility(::ListingVisibility::Public)
+                .only_allow_merge_if_pipeline_succeeds(true)
+                .body())
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"builds_enabled":false,"name":"Name","#,
+                           r#""only_allow_merge_if_build_succeeds":true,"visibility_level":20}"#));
+    }
+}
@@ -0,0 +1,311 @@
+//! Edit project
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#edit-project
+//!
+//! # Edit project
+//!
+//! Updates an existing project.
+//!
+//! If the operation is successful, a status code of `200` and the updated project is returned.
+//!
+//! ```text
+//! PUT /projects/ID
+//! ```
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `name` | string | no | The name of the project |
+//! | `path` | string | no | Custom repository name for the project |
+//! | `description` | string | no | Short project description |
+//! | `default_branch` | string | no | The default branch of the project |
+//! | `issues_enabled` | boolean | no | Enable issues for this project |
+//! | `merge_requests_enabled` | boolean | no | Enable merge requests for this project |
+//! | `jobs_enabled` | boolean | no | Enable jobs for this project |
+//! | `wiki_enabled` | boolean | no | Enable wiki for this project |
+//! | `snippets_enabled` | boolean | no | Enable snippets for this project |
+//! | `container_registry_enabled` | boolean | no | Enable container registry for this project |
+//! | `shared_runners_enabled` | boolean | no | Enable shared runners for this project |
+//! | `visibility` | string | no | `private`, `internal` or `public` |
+//! | `lfs_enabled` | boolean | no | Enable LFS |
+//! | `request_access_enabled` | boolean | no | Allow users to request member access |
+//! | `only_allow_merge_if_pipeline_succeeds` | boolean | no | Set whether merge requests can only be merged with successful jobs |
+//! | `only_allow_merge_if_all_discussions_are_resolved` | boolean | no | Set whether merge requests can only be merged when all the discussions are resolved |
+//!
+//! With API v3, `visibility` is sent as `visibility_level` (`0`, `10` or `20`), `jobs_enabled` as
+//! `builds_enabled` and `only_allow_merge_if_pipeline_succeeds` as
+//! `only_allow_merge_if_build_succeeds`.
+//!
+
+
+use serde_json;
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct ProjectEditorInternal {
+    /// The name of the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Custom repository name for the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// Short project description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// The default branch of the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_branch: Option<String>,
+    /// Enable issues for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issues_enabled: Option<bool>,
+    /// Enable merge requests for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_requests_enabled: Option<bool>,
+    /// Enable jobs (builds with API v3) for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jobs_enabled: Option<bool>,
+    /// Enable wiki for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wiki_enabled: Option<bool>,
+    /// Enable snippets for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippets_enabled: Option<bool>,
+    /// Enable container registry for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container_registry_enabled: Option<bool>,
+    /// Enable shared runners for this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_runners_enabled: Option<bool>,
+    /// Visibility of the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<::ListingVisibility>,
+    /// Enable LFS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lfs_enabled: Option<bool>,
+    /// Allow users to request member access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_access_enabled: Option<bool>,
+    /// Merge requests can only be merged with successful jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_allow_merge_if_pipeline_succeeds: Option<bool>,
+    /// Merge requests can only be merged when all the discussions are resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_allow_merge_if_all_discussions_are_resolved: Option<bool>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectEditor<'a> {
+    gl: &'a ::GitLab,
+    /// The ID or `NAMESPACE/PROJECT_NAME` of the project
+    id: ::projects::ListingId,
+    internal: ProjectEditorInternal,
+}
+
+
+impl<'a> ProjectEditor<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ProjectEditor {
+        ProjectEditor {
+            gl: gl,
+            id: id,
+            internal: ProjectEditorInternal::default(),
+        }
+    }
+
+
+    pub fn name(&'a mut self, name: String) -> &'a mut ProjectEditor {
+        self.internal.name = Some(name);
+        self
+    }
+
+    pub fn path(&'a mut self, path: String) -> &'a mut ProjectEditor {
+        self.internal.path = Some(path);
+        self
+    }
+
+    pub fn description(&'a mut self, description: String) -> &'a mut ProjectEditor {
+        self.internal.description = Some(description);
+        self
+    }
+
+    pub fn default_branch(&'a mut self, default_branch: String) -> &'a mut ProjectEditor {
+        self.internal.default_branch = Some(default_branch);
+        self
+    }
+
+    pub fn issues_enabled(&'a mut self, issues_enabled: bool) -> &'a mut ProjectEditor {
+        self.internal.issues_enabled = Some(issues_enabled);
+        self
+    }
+
+    pub fn merge_requests_enabled(&'a mut self,
+                                  merge_requests_enabled: bool)
+                                  -> &'a mut ProjectEditor {
+        self.internal.merge_requests_enabled = Some(merge_requests_enabled);
+        self
+    }
+
+    /// Enable jobs (builds with API v3).
+    pub fn jobs_enabled(&'a mut self, jobs_enabled: bool) -> &'a mut ProjectEditor {
+        self.internal.jobs_enabled = Some(jobs_enabled);
+        self
+    }
+
+    pub fn wiki_enabled(&'a mut self, wiki_enabled: bool) -> &'a mut ProjectEditor {
+        self.internal.wiki_enabled = Some(wiki_enabled);
+        self
+    }
+
+    pub fn snippets_enabled(&'a mut self, snippets_enabled: bool) -> &'a mut ProjectEditor {
+        self.internal.snippets_enabled = Some(snippets_enabled);
+        self
+    }
+
+    pub fn container_registry_enabled(&'a mut self,
+                                      container_registry_enabled: bool)
+                                      -> &'a mut ProjectEditor {
+        self.internal.container_registry_enabled = Some(container_registry_enabled);
+        self
+    }
+
+    pub fn shared_runners_enabled(&'a mut self,
+                                  shared_runners_enabled: bool)
+                                  -> &'a mut ProjectEditor {
+        self.internal.shared_runners_enabled = Some(shared_runners_enabled);
+        self
+    }
+
+    pub fn visibility(&'a mut self, visibility: ::ListingVisibility) -> &'a mut ProjectEditor {
+        self.internal.visibility = Some(visibility);
+        self
+    }
+
+    pub fn lfs_enabled(&'a mut self, lfs_enabled: bool) -> &'a mut ProjectEditor {
+        self.internal.lfs_enabled = Some(lfs_enabled);
+        self
+    }
+
+    pub fn request_access_enabled(&'a mut self,
+                                  request_access_enabled: bool)
+                                  -> &'a mut ProjectEditor {
+        self.internal.request_access_enabled = Some(request_access_enabled);
+        self
+    }
+
+    /// Merge requests can only be merged with successful jobs (builds with API v3).
+    pub fn only_allow_merge_if_pipeline_succeeds(&'a mut self,
+                                                 only_allow: bool)
+                                                 -> &'a mut ProjectEditor {
+        self.internal.only_allow_merge_if_pipeline_succeeds = Some(only_allow);
+        self
+    }
+
+    pub fn only_allow_merge_if_all_discussions_are_resolved(&'a mut self,
+                                                            only_allow: bool)
+                                                            -> &'a mut ProjectEditor {
+        self.internal.only_allow_merge_if_all_discussions_are_resolved = Some(only_allow);
+        self
+    }
+
+
+    /// Body of the request, with the attributes' names of the API version used.
+    fn body(&self) -> serde_json::Value {
+        let body = serde_json::to_value(&self.internal).unwrap();
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => ::projects::v3_attributes(body),
+            ::ApiVersion::V4 => body,
+        }
+    }
+
+
+    /// Commit the editor: Update the project on GitLab and return it.
+    pub fn edit(&self) -> Result<::projects::Project> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.body()).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}", self.id.to_query())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).edit().build_query();
+        assert_eq!(query, format!("projects/{}", TEST_PROJECT_ID));
+
+        let query = gl.projects()
+            .id(ListingId::NamespaceProject(String::from("group/project")))
+            .edit()
+            .build_query();
+        assert_eq!(query, "projects/group%2Fproject");
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let editor = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).edit();
+        let body = serde_json::to_string(&editor.body()).unwrap();
+        assert_eq!(body, "{}");
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .edit()
+                .name(String::from("Name"))
+                .default_branch(String::from("develop"))
+                .wiki_enabled(false)
+                .visibility(::ListingVisibility::Private)
+                .body())
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"default_branch":"develop","name":"Name","#,
+                           r#""visibility":"private","wiki_enabled":false}"#));
+    }
+
+
+    #[test]
+    fn body_v3() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .edit()
+                .visibility(::ListingVisibility::Private)
+                .jobs_enabled(true)
+                .body())
+            .unwrap();
+        assert_eq!(body, r#"{"builds_enabled":true,"visibility_level":0}"#);
+    }
+}
@@ -0,0 +1,132 @@
+//! Fork project
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#fork-project
+//!
+//! # Fork project
+//!
+//! Forks a project into the user namespace of the authenticated user or the one provided.
+//!
+//! If the operation is successful, a status code of `201` and the newly-created fork is
+//! returned.
+//!
+//! ```text
+//! POST /projects/fork/ID
+//! ```
+//!
+//! With API v4, this is `POST /projects/ID/fork`.
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `namespace` | integer/string | no | The ID or path of the namespace that the project will be forked to |
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct ProjectForkerInternal {
+    /// The ID or path of the namespace that the project will be forked to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectForker<'a> {
+    gl: &'a ::GitLab,
+    /// The ID or `NAMESPACE/PROJECT_NAME` of the project
+    id: ::projects::ListingId,
+    internal: ProjectForkerInternal,
+}
+
+
+impl<'a> ProjectForker<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ProjectForker {
+        ProjectForker {
+            gl: gl,
+            id: id,
+            internal: ProjectForkerInternal::default(),
+        }
+    }
+
+
+    /// The ID or path of the namespace to fork to, instead of the user's namespace.
+    pub fn namespace(&'a mut self, namespace: String) -> &'a mut ProjectForker {
+        self.internal.namespace = Some(namespace);
+        self
+    }
+
+
+    /// Commit the forker: Fork the project on GitLab and return the fork.
+    pub fn fork(&self) -> Result<::projects::Project> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.post(&query, &self.internal).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectForker<'a> {
+    fn build_query(&self) -> String {
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => format!("projects/fork/{}", self.id.to_query()),
+            ::ApiVersion::V4 => format!("projects/{}/fork", self.id.to_query()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).fork().build_query();
+        assert_eq!(query, format!("projects/fork/{}", TEST_PROJECT_ID));
+    }
+
+
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let query = gl.projects()
+            .id(ListingId::NamespaceProject(String::from("group/project")))
+            .fork()
+            .build_query();
+        assert_eq!(query, "projects/group%2Fproject/fork");
+    }
+
+
+    #[test]
+    fn body() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let forker = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).fork();
+        assert_eq!(serde_json::to_string(&forker.internal).unwrap(), "{}");
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .fork()
+                .namespace(String::from("other-group"))
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"namespace":"other-group"}"#);
+    }
+}
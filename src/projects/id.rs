@@ -18,6 +18,22 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//!
+//! # Actions on the project
+//!
+//! The lister also gives access to the following requests on the project:
+//!
+//! | Method | Request | Description |
+//! | ------ | ------- | ----------- |
+//! | `edit()` | `PUT /projects/ID` | See the `edit` module |
+//! | `fork()` | `POST /projects/fork/ID` | See the `fork` module |
+//! | `delete()` | `DELETE /projects/ID` | Remove the project, with all its issues and merge requests |
+//! | `archive()` | `POST /projects/ID/archive` | Archive the project, making it read-only |
+//! | `unarchive()` | `POST /projects/ID/unarchive` | Unarchive the project |
+//! | `star()` | `POST /projects/ID/star` | Star the project |
+//! | `unstar()` | `DELETE /projects/ID/star` | Unstar the project (`POST /projects/ID/unstar` with API v4) |
+//!
+//! Starring a project already starred (or unstarring a project not starred) is not an error.
 
 
 use BuildQuery;
@@ -25,6 +41,11 @@ use BuildQuery;
 use ::errors::*;
 
 
+/// Body of the requests without attributes.
+#[derive(Debug, Serialize)]
+struct ProjectActionInternal {}
+
+
 #[derive(Debug, Clone)]
 pub struct ProjectsLister<'a> {
     gl: &'a ::GitLab,
@@ -61,18 +82,102 @@ impl<'a> ProjectsLister<'a> {
 
         Ok(::merge_requests::MergeRequestsLister::new(self.gl, project.id))
     }
+
+
+    /// Return a lister for the project's branches
+    pub fn branches(self) -> ::projects::id_branches::BranchesLister<'a> {
+        ::projects::id_branches::BranchesLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for a single branch of the project
+    pub fn branch(self, branch: String) -> ::projects::id_branch::BranchLister<'a> {
+        ::projects::id_branch::BranchLister::new(self.gl, self.id, branch)
+    }
+
+    /// Return a lister for the project's events
+    pub fn events(self) -> ::projects::id_events::ProjectEventsLister<'a> {
+        ::projects::id_events::ProjectEventsLister::new(self.gl, self.id)
+    }
+
+    /// Return a lister for the project's hooks
+    pub fn hooks(self) -> ::projects::id_hooks::ProjectHooksLister<'a> {
+        ::projects::id_hooks::ProjectHooksLister::new(self.gl, self.id)
+    }
+
+    /// Return an editor for the project
+    pub fn edit(self) -> ::projects::edit::ProjectEditor<'a> {
+        ::projects::edit::ProjectEditor::new(self.gl, self.id)
+    }
+
+    /// Return a forker for the project
+    pub fn fork(self) -> ::projects::fork::ProjectForker<'a> {
+        ::projects::fork::ProjectForker::new(self.gl, self.id)
+    }
+
+    /// Delete the project on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    /// Archive the project on GitLab and return it.
+    pub fn archive(&self) -> Result<::projects::Project> {
+        self.post_action("archive")
+    }
+
+    /// Unarchive the project on GitLab and return it.
+    pub fn unarchive(&self) -> Result<::projects::Project> {
+        self.post_action("unarchive")
+    }
+
+    /// Star the project on GitLab and return it.
+    pub fn star(&self) -> Result<::projects::Project> {
+        self.post_action("star")
+    }
+
+    /// Unstar the project on GitLab and return it.
+    pub fn unstar(&self) -> Result<::projects::Project> {
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => {
+                let query = format!("{}/star", self.build_query());
+                debug!("query: {:?}", query);
+
+                match self.gl.delete(&query) {
+                    Err(ref e) if e.kind().status() == Some(304) => {
+                        debug!("project not starred");
+                    }
+                    result => result.chain_err(|| format!("cannot delete query {}", query))?,
+                }
+                // The project sent back is discarded by `GitLab::delete()`: get it again.
+                self.list()
+            }
+            ::ApiVersion::V4 => self.post_action("unstar"),
+        }
+    }
+
+
+    /// Send `POST /projects/ID/ACTION` and return the project.
+    fn post_action(&self, action: &str) -> Result<::projects::Project> {
+        let query = format!("{}/{}", self.build_query(), action);
+        debug!("query: {:?}", query);
+
+        match self.gl.post(&query, &ProjectActionInternal {}) {
+            // (Un)starring again: nothing is sent back.
+            Err(ref e) if e.kind().status() == Some(304) => {
+                debug!("project not modified by query {}", query);
+            }
+            result => return result.chain_err(|| format!("cannot post query {}", query)),
+        }
+        self.list()
+    }
 }
 
+
 impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
-        let mut query = String::from("projects/");
-
-        query.push_str(&match self.id {
-            ::projects::ListingId::Id(id) => id.to_string(),
-            ::projects::ListingId::NamespaceProject(ref s) => s.replace("/", "%2F"),
-        });
-
-        query
+        format!("projects/{}", self.id.to_query())
     }
 }
 
@@ -80,6 +185,7 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
 #[cfg(test)]
 mod tests {
     use BuildQuery;
+    use fixtures::project_json;
 
     const TEST_PROJECT_ID: i64 = 123;
     const TEST_PROJECT_NAME: &'static str = "group/project";
@@ -103,4 +209,87 @@ mod tests {
             .build_query();
         assert_eq!(query, expected_string);
     }
+
+
+    #[test]
+    fn actions_through_transport() {
+        use transport::{Method, MockTransport, Response};
+
+        let reply = project_json(TEST_PROJECT_ID, "group", "project");
+        let mock = MockTransport::new();
+        for _ in 0..4 {
+            mock.push_response(Response::new(201, &reply));
+        }
+        mock.push_response(Response::new(204, ""));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let project = || gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID));
+
+        assert_eq!(project().archive().unwrap().id, TEST_PROJECT_ID);
+        assert_eq!(project().unarchive().unwrap().id, TEST_PROJECT_ID);
+        assert_eq!(project().star().unwrap().id, TEST_PROJECT_ID);
+        assert_eq!(project().fork().fork().unwrap().id, TEST_PROJECT_ID);
+        project().delete().unwrap();
+
+        let requests: Vec<_> = mock.requests()
+            .into_iter()
+            .map(|request| (request.method, request.url))
+            .collect();
+        let url = |path: &str| format!("https://localhost/api/v3/projects/{}", path);
+        assert_eq!(requests,
+                   vec![(Method::Post, url("123/archive")),
+                        (Method::Post, url("123/unarchive")),
+                        (Method::Post, url("123/star")),
+                        (Method::Post, url("fork/123")),
+                        (Method::Delete, url("123"))]);
+    }
+
+
+    #[test]
+    fn star_not_modified() {
+        use transport::{Method, MockTransport, Response};
+
+        let reply = project_json(TEST_PROJECT_ID, "group", "project");
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(304, ""));
+        mock.push_response(Response::new(200, &reply));
+        mock.push_response(Response::new(304, ""));
+        mock.push_response(Response::new(200, &reply));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let project = || gl.projects().id(::projects::ListingId::Id(TEST_PROJECT_ID));
+
+        assert_eq!(project().star().unwrap().id, TEST_PROJECT_ID);
+        assert_eq!(project().unstar().unwrap().id, TEST_PROJECT_ID);
+
+        let methods: Vec<_> = mock.requests().into_iter().map(|request| request.method).collect();
+        assert_eq!(methods, vec![Method::Post, Method::Get, Method::Delete, Method::Get]);
+    }
+
+
+    #[test]
+    fn unstar_v4() {
+        use transport::{MockTransport, Response};
+
+        let reply = project_json(TEST_PROJECT_ID, "group", "project");
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(201, &reply));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4)
+            .transport(mock.clone());
+
+        assert_eq!(gl.projects()
+                       .id(::projects::ListingId::Id(TEST_PROJECT_ID))
+                       .unstar()
+                       .unwrap()
+                       .id,
+                   TEST_PROJECT_ID);
+        assert_eq!(mock.requests()[0].url, "https://localhost/api/v4/projects/123/unstar");
+    }
 }
@@ -16,5 +16,276 @@
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
 //! | `branch` | string | yes | The name of the branch |
+//!
+//! The name of the branch is URL-encoded: `feature/x` is requested as `feature%2Fx`.
+//!
+//! # Delete repository branch
+//!
+//! https://docs.gitlab.com/ce/api/branches.html#delete-repository-branch
+//!
+//! ```text
+//! DELETE /projects/ID/repository/branches/BRANCH
+//! ```
+//!
+//! # Protect repository branch
+//!
+//! https://docs.gitlab.com/ce/api/branches.html#protect-repository-branch
+//!
+//! Protects a single branch of a project.
+//!
+//! ```text
+//! PUT /projects/ID/repository/branches/BRANCH/protect
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//! | `branch` | string | yes | The name of the branch |
 //! | `developers_can_push` | boolean | no | Flag if developers can push to the branch |
 //! | `developers_can_merge` | boolean | no | Flag if developers can merge to the branch |
+//!
+//! # Unprotect repository branch
+//!
+//! https://docs.gitlab.com/ce/api/branches.html#unprotect-repository-branch
+//!
+//! Unprotects a single branch of a project.
+//!
+//! ```text
+//! PUT /projects/ID/repository/branches/BRANCH/unprotect
+//! ```
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct BranchLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    /// The name of the branch
+    branch: String,
+}
+
+
+impl<'a> BranchLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, branch: String) -> BranchLister {
+        BranchLister {
+            gl: gl,
+            id: id,
+            branch: branch,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the branch.
+    pub fn list(&self) -> Result<::projects::Branch> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Delete the branch on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+
+    /// Return a protector for the branch
+    pub fn protect(self) -> BranchProtector<'a> {
+        BranchProtector::new(self.gl, self.id, self.branch)
+    }
+
+    /// Unprotect the branch on GitLab and return it.
+    pub fn unprotect(&self) -> Result<::projects::Branch> {
+        let query = format!("{}/unprotect", self.build_query());
+        debug!("query: {:?}", query);
+
+        self.gl
+            .put(&query, &BranchProtectorInternal::default())
+            .chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for BranchLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/repository/branches/{}",
+                self.id.to_query(),
+                ::projects::encode_branch(&self.branch))
+    }
+}
+
+
+#[derive(Default, Debug, Clone, Serialize)]
+struct BranchProtectorInternal {
+    /// Flag if developers can push to the branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    developers_can_push: Option<bool>,
+    /// Flag if developers can merge to the branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    developers_can_merge: Option<bool>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct BranchProtector<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    /// The name of the branch
+    branch: String,
+    internal: BranchProtectorInternal,
+}
+
+
+impl<'a> BranchProtector<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, branch: String) -> BranchProtector {
+        BranchProtector {
+            gl: gl,
+            id: id,
+            branch: branch,
+            internal: BranchProtectorInternal::default(),
+        }
+    }
+
+
+    pub fn developers_can_push(&'a mut self, developers_can_push: bool) -> &'a mut BranchProtector {
+        self.internal.developers_can_push = Some(developers_can_push);
+        self
+    }
+
+    pub fn developers_can_merge(&'a mut self,
+                                developers_can_merge: bool)
+                                -> &'a mut BranchProtector {
+        self.internal.developers_can_merge = Some(developers_can_merge);
+        self
+    }
+
+
+    /// Commit the protector: Protect the branch on GitLab and return it.
+    pub fn protect(&self) -> Result<::projects::Branch> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.put(&query, &self.internal).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for BranchProtector<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/repository/branches/{}/protect",
+                self.id.to_query(),
+                ::projects::encode_branch(&self.branch))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        let branch = |name: &str| {
+            gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).branch(String::from(name))
+        };
+
+        assert_eq!(branch("master").build_query(),
+                   format!("projects/{}/repository/branches/master", TEST_PROJECT_ID));
+        assert_eq!(branch("feature/x").build_query(),
+                   format!("projects/{}/repository/branches/feature%2Fx", TEST_PROJECT_ID));
+        assert_eq!(branch("feature/x").protect().build_query(),
+                   format!("projects/{}/repository/branches/feature%2Fx/protect",
+                           TEST_PROJECT_ID));
+    }
+
+
+    #[test]
+    fn body_protect() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let protector = gl.projects()
+            .id(ListingId::Id(TEST_PROJECT_ID))
+            .branch(String::from("master"))
+            .protect();
+        assert_eq!(serde_json::to_string(&protector.internal).unwrap(), "{}");
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .branch(String::from("master"))
+                .protect()
+                .developers_can_push(false)
+                .developers_can_merge(true)
+                .internal)
+            .unwrap();
+        assert_eq!(body, r#"{"developers_can_push":false,"developers_can_merge":true}"#);
+    }
+
+
+    #[test]
+    fn branch_through_transport() {
+        use transport::{Method, MockTransport, Response};
+
+        let branch_json = |protected: bool| {
+            format!(r#"{{"name": "release/1.0", "protected": {},
+                "developers_can_push": false, "developers_can_merge": {},
+                "commit": {{"id": "7b5c3cc8be40ee161ae89a06bba6229da1032a0c",
+                           "short_id": "7b5c3cc8", "title": "Add projects API",
+                           "author_name": "John Smith", "author_email": "john@example.com",
+                           "created_at": "2012-06-27T05:51:39-07:00"}}}}"#,
+                    protected,
+                    protected)
+        };
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &branch_json(false)));
+        mock.push_response(Response::new(200, &branch_json(true)));
+        mock.push_response(Response::new(200, &branch_json(false)));
+        mock.push_response(Response::new(204, ""));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let branch = || {
+            gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).branch(String::from("release/1.0"))
+        };
+
+        let listed = branch().list().unwrap();
+        assert!(!listed.protected);
+        assert_eq!(listed.merged, None);
+        assert_eq!(listed.commit.short_id, Some(String::from("7b5c3cc8")));
+
+        assert!(branch().protect().developers_can_merge(true).protect().unwrap().protected);
+        assert!(!branch().unprotect().unwrap().protected);
+        branch().delete().unwrap();
+
+        let requests: Vec<_> = mock.requests()
+            .into_iter()
+            .map(|request| (request.method, request.url))
+            .collect();
+        let url = |path: &str| {
+            format!("https://localhost/api/v3/projects/123/repository/branches/release%2F1.0{}",
+                    path)
+        };
+        assert_eq!(requests,
+                   vec![(Method::Get, url("")),
+                        (Method::Put, url("/protect")),
+                        (Method::Put, url("/unprotect")),
+                        (Method::Delete, url(""))]);
+    }
+}
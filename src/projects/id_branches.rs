@@ -16,4 +16,265 @@
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
 //!
+//! # Create repository branch
 //!
+//! https://docs.gitlab.com/ce/api/branches.html#create-repository-branch
+//!
+//! ```text
+//! POST /projects/ID/repository/branches
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//! | `branch` | string | yes | The name of the branch |
+//! | `ref` | string | yes | The branch name or commit SHA to create branch from |
+//!
+//! With API v3, `branch` is named `branch_name`.
+//!
+//! # Delete merged branches
+//!
+//! https://docs.gitlab.com/ce/api/branches.html#delete-merged-branches
+//!
+//! Will delete all branches that are merged into the project's default branch. Protected
+//! branches will not be deleted as part of this operation.
+//!
+//! ```text
+//! DELETE /projects/ID/repository/merged_branches
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//!
+
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct BranchesLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> Lister<::projects::Branches> for BranchesLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of branches.
+    fn list(&self) -> Result<::projects::Branches> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of branches.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::Branches> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Branches>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> BranchesLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> BranchesLister {
+        BranchesLister { gl: gl, id: id }
+    }
+
+    /// Return a creator of the branch `branch`, starting at `ref_` (a branch name or a commit SHA)
+    pub fn create(self, branch: String, ref_: String) -> BranchCreator<'a> {
+        BranchCreator::new(self.gl, self.id, branch, ref_)
+    }
+
+    /// Delete all the branches merged into the project's default branch, except the protected
+    /// ones.
+    ///
+    /// GitLab deletes them asynchronously: they might still be listed right after this returns.
+    pub fn delete_merged(&self) -> Result<()> {
+        let query = format!("projects/{}/repository/merged_branches", self.id.to_query());
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for BranchesLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/repository/branches", self.id.to_query())
+    }
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+struct BranchCreatorInternal {
+    /// The name of the branch
+    branch: String,
+    /// The branch name or commit SHA to create branch from
+    #[serde(rename = "ref")]
+    ref_: String,
+}
+
+
+/// Body of the request with API v3, where `branch` is named `branch_name`.
+#[derive(Debug, Serialize)]
+struct BranchCreatorInternalV3<'a> {
+    branch_name: &'a String,
+    #[serde(rename = "ref")]
+    ref_: &'a String,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct BranchCreator<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    internal: BranchCreatorInternal,
+}
+
+
+impl<'a> BranchCreator<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: ::projects::ListingId,
+               branch: String,
+               ref_: String)
+               -> BranchCreator {
+        BranchCreator {
+            gl: gl,
+            id: id,
+            internal: BranchCreatorInternal {
+                branch: branch,
+                ref_: ref_,
+            },
+        }
+    }
+
+    /// Commit the creator: Create the branch on GitLab and return it.
+    pub fn create(&self) -> Result<::projects::Branch> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let result = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => {
+                let internal = BranchCreatorInternalV3 {
+                    branch_name: &self.internal.branch,
+                    ref_: &self.internal.ref_,
+                };
+                self.gl.post(&query, &internal)
+            }
+            ::ApiVersion::V4 => self.gl.post(&query, &self.internal),
+        };
+        result.chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for BranchCreator<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/repository/branches", self.id.to_query())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/repository/branches", TEST_PROJECT_ID);
+        let query = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).branches().build_query();
+        assert_eq!(query, expected_string);
+
+        let query = gl.projects()
+            .id(ListingId::Id(TEST_PROJECT_ID))
+            .branches()
+            .create(String::from("feature"), String::from("master"))
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn branches_through_transport() {
+        use Lister;
+        use transport::{Method, MockTransport, Response};
+
+        let branch_json = r#"{"name": "feature/x", "merged": false, "protected": false,
+            "developers_can_push": false, "developers_can_merge": false,
+            "commit": {"id": "7b5c3cc8be40ee161ae89a06bba6229da1032a0c",
+                       "message": "add projects API", "parent_ids": [],
+                       "author_name": "John Smith", "author_email": "john@example.com",
+                       "authored_date": "2012-06-27T05:51:39-07:00",
+                       "committer_name": "John Smith", "committer_email": "john@example.com",
+                       "committed_date": "2012-06-28T03:44:20-07:00"}}"#;
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, &format!("[{}]", branch_json)));
+        mock.push_response(Response::new(201, branch_json));
+        mock.push_response(Response::new(201, branch_json));
+        mock.push_response(Response::new(202, r#"{"message": "202 Accepted"}"#));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let branches = || gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).branches();
+
+        let listed = branches().list().unwrap();
+        assert_eq!(listed[0].name, "feature/x");
+        assert_eq!(listed[0].commit.author_name, "John Smith");
+
+        let create = |gl: &::GitLab| {
+            gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .branches()
+                .create(String::from("feature/x"), String::from("master"))
+                .create()
+                .unwrap()
+        };
+        create(&gl);
+        create(&::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4)
+            .transport(mock.clone()));
+        branches().delete_merged().unwrap();
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|request| request.method).collect();
+        assert_eq!(methods, vec![Method::Get, Method::Post, Method::Post, Method::Delete]);
+        assert_eq!(requests[1].body.as_ref().unwrap(),
+                   r#"{"branch_name":"feature/x","ref":"master"}"#);
+        assert_eq!(requests[2].body.as_ref().unwrap(),
+                   r#"{"branch":"feature/x","ref":"master"}"#);
+        assert_eq!(requests[3].url,
+                   "https://localhost/api/v3/projects/123/repository/merged_branches");
+    }
+}
@@ -16,5 +16,189 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID or `NAMESPACE/PROJECT_NAME` of the project |
+//! | `action` | string | no | Include only events of a particular action type (API v4 only) |
+//! | `target_type` | string | no | Include only events of a particular target type (API v4 only) |
+//! | `before` | date | no | Include only events created before a particular date, in the format `YYYY-MM-DD` (API v4 only) |
+//! | `after` | date | no | Include only events created after a particular date, in the format `YYYY-MM-DD` (API v4 only) |
+//! | `sort` | string | no | Sort events in `asc` or `desc` order by `created_at` (API v4 only) |
 //!
 //!
+//!
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct ProjectEventsListerInternal {
+    /// Include only events of a particular action type
+    action: Option<::events::ListingAction>,
+    /// Include only events of a particular target type
+    target_type: Option<::events::ListingTargetType>,
+    /// Include only events created before a particular date
+    #[serde(default, with = "::dates::optional_date")]
+    before: Option<::dates::Date>,
+    /// Include only events created after a particular date
+    #[serde(default, with = "::dates::optional_date")]
+    after: Option<::dates::Date>,
+    /// Sort events by `created_at`. Default is `::ListingSort::Desc`.
+    sort: Option<::ListingSort>,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectEventsLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID or `NAMESPACE/PROJECT_NAME` of the project
+    id: ::projects::ListingId,
+    internal: ProjectEventsListerInternal,
+}
+
+
+impl<'a> Lister<::events::Events> for ProjectEventsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of events.
+    fn list(&self) -> Result<::events::Events> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of events.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::events::Events> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self, page: u16, per_page: u16) -> Result<::pagination::Page<::events::Events>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> ProjectEventsLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ProjectEventsLister {
+        ProjectEventsLister {
+            gl: gl,
+            id: id,
+            internal: ProjectEventsListerInternal::default(),
+        }
+    }
+
+
+    pub fn action(&'a mut self, action: ::events::ListingAction) -> &'a mut ProjectEventsLister {
+        self.internal.action = Some(action);
+        self
+    }
+
+    pub fn target_type(&'a mut self,
+                       target_type: ::events::ListingTargetType)
+                       -> &'a mut ProjectEventsLister {
+        self.internal.target_type = Some(target_type);
+        self
+    }
+
+    /// Only events created before this date, sent in the format YEAR-MONTH-DAY (e.g.
+    /// `2017-03-11`).
+    pub fn before(&'a mut self, before: ::dates::Date) -> &'a mut ProjectEventsLister {
+        self.internal.before = Some(before);
+        self
+    }
+
+    /// Only events created after this date, sent in the format YEAR-MONTH-DAY (e.g.
+    /// `2017-03-11`).
+    pub fn after(&'a mut self, after: ::dates::Date) -> &'a mut ProjectEventsLister {
+        self.internal.after = Some(after);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut ProjectEventsLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectEventsLister<'a> {
+    fn build_query(&self) -> String {
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        ::join_query(&format!("projects/{}/events", self.id.to_query()), &[&encoded])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    use events::{ListingAction, ListingTargetType};
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).events().build_query();
+        assert_eq!(query, format!("projects/{}/events", TEST_PROJECT_ID));
+    }
+
+
+    #[test]
+    fn build_query_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects()
+            .id(ListingId::NamespaceProject(String::from("group/project")))
+            .events()
+            .action(ListingAction::Commented)
+            .target_type(ListingTargetType::Issue)
+            .after("2017-02-01".parse().unwrap())
+            .build_query();
+        assert_eq!(query,
+                   "projects/group%2Fproject/events?action=commented&target_type=issue&\
+                    after=2017-02-01");
+    }
+
+
+    #[test]
+    fn events_through_transport() {
+        use Lister;
+        use events::EventAction;
+        use transport::{Method, MockTransport, Response};
+
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200,
+                                         r#"[{"title": null, "project_id": 123,
+                                            "action_name": "opened", "target_id": 160,
+                                            "target_iid": 3, "target_type": "Issue",
+                                            "author_id": 25, "target_title": "Title",
+                                            "created_at": "2017-02-09T10:43:19.426Z",
+                                            "author_username": "user3"}]"#));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+
+        let events = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).events().list().unwrap();
+        assert_eq!(events[0].action_name, EventAction::Opened);
+        assert_eq!(events[0].target_iid, Some(3));
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(requests[0].url, "https://localhost/api/v3/projects/123/events");
+    }
+}
@@ -15,3 +15,336 @@
 //! | Attribute | Type | Required | Description |
 //! | --------- | ---- | -------- | ----------- |
 //! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//!
+//! # Add project hook
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#add-project-hook
+//!
+//! Adds a hook to a specified project.
+//!
+//! ```text
+//! POST /projects/ID/hooks
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//! | `url` | string | yes | The hook URL |
+//! | `push_events` | boolean | no | Trigger hook on push events |
+//! | `issues_events` | boolean | no | Trigger hook on issues events |
+//! | `confidential_issues_events` | boolean | no | Trigger hook on confidential issues events |
+//! | `merge_requests_events` | boolean | no | Trigger hook on merge requests events |
+//! | `tag_push_events` | boolean | no | Trigger hook on tag push events |
+//! | `note_events` | boolean | no | Trigger hook on note events |
+//! | `job_events` | boolean | no | Trigger hook on job events |
+//! | `pipeline_events` | boolean | no | Trigger hook on pipeline events |
+//! | `wiki_page_events` | boolean | no | Trigger hook on wiki events |
+//! | `enable_ssl_verification` | boolean | no | Do SSL verification when triggering the hook |
+//! | `token` | string | no | Secret token to validate received payloads; this will not be returned in the response |
+//!
+//! With API v3, `job_events` is named `build_events`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gitlab_api::GitLab;
+//! use gitlab_api::Lister;
+//! use gitlab_api::projects::ListingId;
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+//!
+//! let project = || gl.projects().id(ListingId::NamespaceProject(String::from("group/project")));
+//! let hooks = project().hooks().list().unwrap();
+//! if !hooks.iter().any(|hook| hook.url == "https://ci.example.com/hook") {
+//!     project()
+//!         .hooks()
+//!         .add(String::from("https://ci.example.com/hook"))
+//!         .merge_requests_events(true)
+//!         .enable_ssl_verification(true)
+//!         .add()
+//!         .unwrap();
+//! }
+//! ```
+
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectHooksLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+}
+
+
+impl<'a> Lister<::projects::ProjectHooks> for ProjectHooksLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of project hooks.
+    fn list(&self) -> Result<::projects::ProjectHooks> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of project hooks.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::ProjectHooks> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::ProjectHooks>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> ProjectHooksLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId) -> ProjectHooksLister {
+        ProjectHooksLister { gl: gl, id: id }
+    }
+
+    /// Return a lister for a single hook of the project
+    pub fn id(self, hook_id: i64) -> ::projects::id_hooks_id::ProjectHookLister<'a> {
+        ::projects::id_hooks_id::ProjectHookLister::new(self.gl, self.id, hook_id)
+    }
+
+    /// Return an adder of a hook to `url`
+    pub fn add(self, url: String) -> ProjectHookAdder<'a> {
+        ProjectHookAdder::new(self.gl, self.id, url)
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectHooksLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/hooks", self.id.to_query())
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectHookAdder<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    internal: ::projects::ProjectHookInternal,
+}
+
+
+impl<'a> ProjectHookAdder<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, url: String) -> ProjectHookAdder {
+        ProjectHookAdder {
+            gl: gl,
+            id: id,
+            internal: ::projects::ProjectHookInternal::new(url),
+        }
+    }
+
+
+    /// Trigger the hook on push events.
+    pub fn push_events(&'a mut self, push_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.push_events = Some(push_events);
+        self
+    }
+
+    /// Trigger the hook on issues events.
+    pub fn issues_events(&'a mut self, issues_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.issues_events = Some(issues_events);
+        self
+    }
+
+    /// Trigger the hook on confidential issues events.
+    pub fn confidential_issues_events(&'a mut self,
+                                      confidential_issues_events: bool)
+                                      -> &'a mut ProjectHookAdder {
+        self.internal.confidential_issues_events = Some(confidential_issues_events);
+        self
+    }
+
+    /// Trigger the hook on merge requests events.
+    pub fn merge_requests_events(&'a mut self,
+                                 merge_requests_events: bool)
+                                 -> &'a mut ProjectHookAdder {
+        self.internal.merge_requests_events = Some(merge_requests_events);
+        self
+    }
+
+    /// Trigger the hook on tag push events.
+    pub fn tag_push_events(&'a mut self, tag_push_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.tag_push_events = Some(tag_push_events);
+        self
+    }
+
+    /// Trigger the hook on note (comment) events.
+    pub fn note_events(&'a mut self, note_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.note_events = Some(note_events);
+        self
+    }
+
+    /// Trigger the hook on job events (build events with API v3).
+    pub fn job_events(&'a mut self, job_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.job_events = Some(job_events);
+        self
+    }
+
+    /// Trigger the hook on pipeline events.
+    pub fn pipeline_events(&'a mut self, pipeline_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.pipeline_events = Some(pipeline_events);
+        self
+    }
+
+    /// Trigger the hook on wiki events.
+    pub fn wiki_page_events(&'a mut self, wiki_page_events: bool) -> &'a mut ProjectHookAdder {
+        self.internal.wiki_page_events = Some(wiki_page_events);
+        self
+    }
+
+    /// Verify the SSL certificate of the hook URL when triggering it.
+    pub fn enable_ssl_verification(&'a mut self,
+                                   enable_ssl_verification: bool)
+                                   -> &'a mut ProjectHookAdder {
+        self.internal.enable_ssl_verification = Some(enable_ssl_verification);
+        self
+    }
+
+    /// Secret token sent in the `X-Gitlab-Token` header, to validate the payloads received.
+    pub fn token(&'a mut self, token: String) -> &'a mut ProjectHookAdder {
+        self.internal.token = Some(token);
+        self
+    }
+
+
+    /// Commit the adder: Add the hook to the project on GitLab and return it.
+    pub fn add(&self) -> Result<::projects::ProjectHook> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = self.internal.body(self.gl.get_api_version());
+        self.gl.post(&query, &body).chain_err(|| format!("cannot post query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectHookAdder<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/hooks", self.id.to_query())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+    use serde_json;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let query = gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).hooks().build_query();
+        assert_eq!(query, format!("projects/{}/hooks", TEST_PROJECT_ID));
+
+        let query = gl.projects()
+            .id(ListingId::NamespaceProject(String::from("group/project")))
+            .hooks()
+            .add(String::from("https://example.com/hook"))
+            .build_query();
+        assert_eq!(query, "projects/group%2Fproject/hooks");
+    }
+
+
+    #[test]
+    fn body_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let adder = gl.projects()
+            .id(ListingId::Id(TEST_PROJECT_ID))
+            .hooks()
+            .add(String::from("https://example.com/hook"));
+        let body = serde_json::to_string(&adder.internal.body(::ApiVersion::V4)).unwrap();
+        assert_eq!(body, r#"{"url":"https://example.com/hook"}"#);
+    }
+
+
+    #[test]
+    fn body_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .hooks()
+                .add(String::from("https://example.com/hook"))
+                .push_events(false)
+                .issues_events(true)
+                .merge_requests_events(true)
+                .tag_push_events(true)
+                .note_events(true)
+                .job_events(true)
+                .pipeline_events(true)
+                .wiki_page_events(true)
+                .enable_ssl_verification(true)
+                .token(String::from("secret"))
+                .internal
+                .body(::ApiVersion::V4))
+            .unwrap();
+        assert_eq!(body,
+                   concat!(r#"{"enable_ssl_verification":true,"issues_events":true,"#,
+                           r#""job_events":true,"merge_requests_events":true,"note_events":true,"#,
+                           r#""pipeline_events":true,"push_events":false,"#,
+                           r#""tag_push_events":true,"token":"secret","#,
+                           r#""url":"https://example.com/hook","wiki_page_events":true}"#));
+
+        let body = serde_json::to_string(&gl.projects()
+                .id(ListingId::Id(TEST_PROJECT_ID))
+                .hooks()
+                .add(String::from("https://example.com/hook"))
+                .job_events(true)
+                .internal
+                .body(::ApiVersion::V3))
+            .unwrap();
+        assert_eq!(body, r#"{"build_events":true,"url":"https://example.com/hook"}"#);
+    }
+
+    #[test]
+    fn token_not_in_debug() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let adder = format!("{:?}",
+                            gl.projects()
+                                .id(ListingId::Id(TEST_PROJECT_ID))
+                                .hooks()
+                                .add(String::from("https://example.com/hook"))
+                                .token(String::from("secret")));
+        assert!(!adder.contains("secret"));
+        assert!(adder.contains(r#"token: Some("XXXXXXXXXXXXXXXXXXXX")"#));
+
+        let editor = format!("{:?}",
+                             gl.projects()
+                                 .id(ListingId::Id(TEST_PROJECT_ID))
+                                 .hooks()
+                                 .id(1)
+                                 .edit(String::from("https://example.com/hook"))
+                                 .token(String::from("secret")));
+        assert!(!editor.contains("secret"));
+    }
+}
@@ -17,4 +17,287 @@
 //! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
 //! | `hook_id` | integer | yes | The ID of a project hook |
 //!
+//! # Edit project hook
 //!
+//! https://docs.gitlab.com/ce/api/projects.html#edit-project-hook
+//!
+//! Edits a hook for a specified project.
+//!
+//! ```text
+//! PUT /projects/ID/hooks/HOOK_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//! | `hook_id` | integer | yes | The ID of the project hook |
+//! | `url` | string | yes | The hook URL |
+//! | `push_events` | boolean | no | Trigger hook on push events |
+//! | `issues_events` | boolean | no | Trigger hook on issues events |
+//! | `confidential_issues_events` | boolean | no | Trigger hook on confidential issues events |
+//! | `merge_requests_events` | boolean | no | Trigger hook on merge requests events |
+//! | `tag_push_events` | boolean | no | Trigger hook on tag push events |
+//! | `note_events` | boolean | no | Trigger hook on note events |
+//! | `job_events` | boolean | no | Trigger hook on job events |
+//! | `pipeline_events` | boolean | no | Trigger hook on pipeline events |
+//! | `wiki_page_events` | boolean | no | Trigger hook on wiki events |
+//! | `enable_ssl_verification` | boolean | no | Do SSL verification when triggering the hook |
+//! | `token` | string | no | Secret token to validate received payloads; this will not be returned in the response |
+//!
+//! With API v3, `job_events` is named `build_events`.
+//!
+//! # Delete project hook
+//!
+//! https://docs.gitlab.com/ce/api/projects.html#delete-project-hook
+//!
+//! Removes a hook from a project.
+//!
+//! ```text
+//! DELETE /projects/ID/hooks/HOOK_ID
+//! ```
+//!
+//! Parameters:
+//!
+//! | Attribute | Type | Required | Description |
+//! | --------- | ---- | -------- | ----------- |
+//! | `id` | integer/string | yes | The ID of the project or `NAMESPACE/PROJECT_NAME` |
+//! | `hook_id` | integer | yes | The ID of the project hook |
+//!
+
+
+use BuildQuery;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectHookLister<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    /// The ID of a project hook
+    hook_id: i64,
+}
+
+
+impl<'a> ProjectHookLister<'a> {
+    pub fn new(gl: &'a ::GitLab, id: ::projects::ListingId, hook_id: i64) -> ProjectHookLister {
+        ProjectHookLister {
+            gl: gl,
+            id: id,
+            hook_id: hook_id,
+        }
+    }
+
+
+    /// Commit the lister: Query GitLab and return the project hook.
+    pub fn list(&self) -> Result<::projects::ProjectHook> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Return an editor for the hook, pointing it to `url`
+    pub fn edit(self, url: String) -> ProjectHookEditor<'a> {
+        ProjectHookEditor::new(self.gl, self.id, self.hook_id, url)
+    }
+
+    /// Delete the hook on GitLab.
+    pub fn delete(&self) -> Result<()> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.delete(&query).chain_err(|| format!("cannot delete query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectHookLister<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/hooks/{}", self.id.to_query(), self.hook_id)
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectHookEditor<'a> {
+    gl: &'a ::GitLab,
+    /// The ID of the project or `NAMESPACE/PROJECT_NAME`
+    id: ::projects::ListingId,
+    /// The ID of the project hook
+    hook_id: i64,
+    internal: ::projects::ProjectHookInternal,
+}
+
+
+impl<'a> ProjectHookEditor<'a> {
+    pub fn new(gl: &'a ::GitLab,
+               id: ::projects::ListingId,
+               hook_id: i64,
+               url: String)
+               -> ProjectHookEditor {
+        ProjectHookEditor {
+            gl: gl,
+            id: id,
+            hook_id: hook_id,
+            internal: ::projects::ProjectHookInternal::new(url),
+        }
+    }
+
+
+    /// Trigger the hook on push events.
+    pub fn push_events(&'a mut self, push_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.push_events = Some(push_events);
+        self
+    }
+
+    /// Trigger the hook on issues events.
+    pub fn issues_events(&'a mut self, issues_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.issues_events = Some(issues_events);
+        self
+    }
+
+    /// Trigger the hook on confidential issues events.
+    pub fn confidential_issues_events(&'a mut self,
+                                      confidential_issues_events: bool)
+                                      -> &'a mut ProjectHookEditor {
+        self.internal.confidential_issues_events = Some(confidential_issues_events);
+        self
+    }
+
+    /// Trigger the hook on merge requests events.
+    pub fn merge_requests_events(&'a mut self,
+                                 merge_requests_events: bool)
+                                 -> &'a mut ProjectHookEditor {
+        self.internal.merge_requests_events = Some(merge_requests_events);
+        self
+    }
+
+    /// Trigger the hook on tag push events.
+    pub fn tag_push_events(&'a mut self, tag_push_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.tag_push_events = Some(tag_push_events);
+        self
+    }
+
+    /// Trigger the hook on note (comment) events.
+    pub fn note_events(&'a mut self, note_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.note_events = Some(note_events);
+        self
+    }
+
+    /// Trigger the hook on job events (build events with API v3).
+    pub fn job_events(&'a mut self, job_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.job_events = Some(job_events);
+        self
+    }
+
+    /// Trigger the hook on pipeline events.
+    pub fn pipeline_events(&'a mut self, pipeline_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.pipeline_events = Some(pipeline_events);
+        self
+    }
+
+    /// Trigger the hook on wiki events.
+    pub fn wiki_page_events(&'a mut self, wiki_page_events: bool) -> &'a mut ProjectHookEditor {
+        self.internal.wiki_page_events = Some(wiki_page_events);
+        self
+    }
+
+    /// Verify the SSL certificate of the hook URL when triggering it.
+    pub fn enable_ssl_verification(&'a mut self,
+                                   enable_ssl_verification: bool)
+                                   -> &'a mut ProjectHookEditor {
+        self.internal.enable_ssl_verification = Some(enable_ssl_verification);
+        self
+    }
+
+    /// Secret token sent in the `X-Gitlab-Token` header, to validate the payloads received.
+    pub fn token(&'a mut self, token: String) -> &'a mut ProjectHookEditor {
+        self.internal.token = Some(token);
+        self
+    }
+
+
+    /// Commit the editor: Update the hook on GitLab and return it.
+    pub fn edit(&self) -> Result<::projects::ProjectHook> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        let body = self.internal.body(self.gl.get_api_version());
+        self.gl.put(&query, &body).chain_err(|| format!("cannot put query {}", query))
+    }
+}
+
+
+impl<'a> BuildQuery for ProjectHookEditor<'a> {
+    fn build_query(&self) -> String {
+        format!("projects/{}/hooks/{}", self.id.to_query(), self.hook_id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+    use projects::ListingId;
+
+    const TEST_PROJECT_ID: i64 = 123;
+    const TEST_HOOK_ID: i64 = 456;
+
+
+    #[test]
+    fn build_query_default() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+
+        let expected_string = format!("projects/{}/hooks/{}", TEST_PROJECT_ID, TEST_HOOK_ID);
+        let hook = || gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).hooks().id(TEST_HOOK_ID);
+        assert_eq!(hook().build_query(), expected_string);
+        assert_eq!(hook().edit(String::from("https://example.com/hook")).build_query(),
+                   expected_string);
+    }
+
+
+    #[test]
+    fn hook_through_transport() {
+        use transport::{Method, MockTransport, Response};
+
+        let hook_json = r#"{"id": 456, "url": "https://example.com/hook", "project_id": 123,
+            "push_events": true, "issues_events": false, "merge_requests_events": true,
+            "tag_push_events": false, "note_events": false, "build_events": true,
+            "pipeline_events": false, "wiki_page_events": false,
+            "enable_ssl_verification": true, "created_at": "2017-02-28T14:32:00.000Z"}"#;
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, hook_json));
+        mock.push_response(Response::new(200, hook_json));
+        mock.push_response(Response::new(200, hook_json));
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .transport(mock.clone());
+        let hook = || gl.projects().id(ListingId::Id(TEST_PROJECT_ID)).hooks().id(TEST_HOOK_ID);
+
+        let listed = hook().list().unwrap();
+        assert_eq!(listed.build_events, Some(true));
+        assert_eq!(listed.job_events, None);
+        assert!(listed.enable_ssl_verification);
+
+        let edited = hook()
+            .edit(String::from("https://example.com/hook"))
+            .enable_ssl_verification(true)
+            .edit()
+            .unwrap();
+        assert_eq!(edited.id, TEST_HOOK_ID);
+
+        hook().delete().unwrap();
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|request| request.method).collect();
+        assert_eq!(methods, vec![Method::Get, Method::Put, Method::Delete]);
+        assert_eq!(requests[1].body.as_ref().unwrap(),
+                   r#"{"enable_ssl_verification":true,"url":"https://example.com/hook"}"#);
+    }
+}
@@ -10,6 +10,8 @@
 //! GET /projects
 //! ```
 //!
+//! With API v4, this is `GET /projects?membership=true`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -23,13 +25,20 @@
 //!
 
 
+use std;
+
+use serde_json;
 use serde_urlencoded;
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
 
 use BuildQuery;
 use Lister;
 
 
 pub mod all;
+pub mod create;
+pub mod edit;
+pub mod fork;
 pub mod id_branches;
 pub mod id_branch;
 pub mod id_events;
@@ -126,7 +135,54 @@ pub enum ListingId {
 }
 
 
+impl ListingId {
+    /// The ID, or the URL-encoded `NAMESPACE/PROJECT_NAME`, as found in queries.
+    fn to_query(&self) -> String {
+        match *self {
+            ListingId::Id(id) => id.to_string(),
+            ListingId::NamespaceProject(ref s) => s.replace("/", "%2F"),
+        }
+    }
+}
+
+
+/// URL-encode a branch name (`feature/x` becomes `feature%2Fx`) to use it in a query.
+fn encode_branch(branch: &str) -> String {
+    utf8_percent_encode(branch, PATH_SEGMENT_ENCODE_SET).to_string()
+}
+
+
+/// Rename the attributes of a project (or of a project hook) in the `body` of a request from their
+/// API v4 names to their API v3 ones.
+fn v3_attributes(body: serde_json::Value) -> serde_json::Value {
+    let mut attributes = match body {
+        serde_json::Value::Object(attributes) => attributes,
+        body => return body,
+    };
+
+    if let Some(visibility) = attributes.remove("visibility") {
+        let level = match visibility.as_str() {
+            Some("public") => 20,
+            Some("internal") => 10,
+            _ => 0,
+        };
+        attributes.insert(String::from("visibility_level"), serde_json::Value::from(level));
+    }
+    for &(v4, v3) in &[("jobs_enabled", "builds_enabled"),
+                       ("job_events", "build_events"),
+                       ("only_allow_merge_if_pipeline_succeeds",
+                        "only_allow_merge_if_build_succeeds")] {
+        if let Some(value) = attributes.remove(v4) {
+            attributes.insert(String::from(v3), value);
+        }
+    }
+
+    serde_json::Value::Object(attributes)
+}
+
+
 type AllProjectListerInternal = OwnedProjectListerInternal;
+type StarredProjectListerInternal = OwnedProjectListerInternal;
 
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -152,8 +208,10 @@ pub struct ProjectNamespace {
     pub name: String,
     pub path: String,
     pub owner_id: Option<i64>,  // FIXME: Why would a project not have this?
-    pub created_at: Option<String>,  // FIXME: Date instead?
-    pub updated_at: Option<String>,  // FIXME: Date instead?
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub created_at: Option<::dates::DateTime>,
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub updated_at: Option<::dates::DateTime>,
     pub description: Option<String>,
     pub avatar: Option<ProjectNamespaceAvatar>,
     pub membership_lock: Option<bool>,
@@ -162,10 +220,14 @@ pub struct ProjectNamespace {
     pub request_access_enabled: Option<bool>,
     pub ldap_sync_status: Option<String>,
     pub ldap_sync_error: Option<String>,  // FIXME: Is String the proper type?
-    pub ldap_sync_last_update_at: Option<String>,  // FIXME: Is String the proper type?
-    pub ldap_sync_last_successful_update_at: Option<String>,  // FIXME: Is String the proper type?
-    pub ldap_sync_last_sync_at: Option<String>,  // FIXME: Is String the proper type?
-    pub deleted_at: Option<String>,  // FIXME: Is String the proper type?
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub ldap_sync_last_update_at: Option<::dates::DateTime>,
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub ldap_sync_last_successful_update_at: Option<::dates::DateTime>,
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub ldap_sync_last_sync_at: Option<::dates::DateTime>,
+    #[serde(default, with = "::dates::optional_datetime")]
+    pub deleted_at: Option<::dates::DateTime>,
     pub lfs_enabled: Option<String>,  // FIXME: Is String the proper type?
     pub repository_size_limit: Option<String>  // FIXME: Is String the proper type?
 }
@@ -211,9 +273,10 @@ pub struct Project {
     pub description: String,
     pub default_branch: Option<String>,
     pub tag_list: Vec<String>,
-    pub public: bool,
+    pub public: Option<bool>,  // API v3 only
     pub archived: bool,
-    pub visibility_level: i64,
+    pub visibility_level: Option<i64>,  // API v3 only
+    pub visibility: Option<::ListingVisibility>,  // API v4 only
     pub ssh_url_to_repo: String,
     pub http_url_to_repo: String,
     pub web_url: String,
@@ -226,10 +289,13 @@ pub struct Project {
     pub issues_enabled: Option<bool>,
     pub merge_requests_enabled: Option<bool>,
     pub wiki_enabled: Option<bool>,
-    pub builds_enabled: Option<bool>,
+    pub builds_enabled: Option<bool>,  // API v3 only
+    pub jobs_enabled: Option<bool>,  // API v4 only
     pub snippets_enabled: Option<bool>,
-    pub created_at: String,  // FIXME: Date instead?
-    pub last_activity_at: String,  // FIXME: Date instead?
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+    #[serde(with = "::dates::datetime")]
+    pub last_activity_at: ::dates::DateTime,
     pub shared_runners_enabled: bool,
     pub lfs_enabled: bool,
     pub creator_id: i64,
@@ -240,9 +306,11 @@ pub struct Project {
     pub forks_count: i64,
     pub open_issues_count: Option<i64>,
     pub runners_token: Option<String>,
-    pub public_builds: bool,
+    pub public_builds: Option<bool>,  // API v3 only
+    pub public_jobs: Option<bool>,  // API v4 only
     pub shared_with_groups: Vec<ProjectSharedWithGroup>,
-    pub only_allow_merge_if_build_succeeds: bool,
+    pub only_allow_merge_if_build_succeeds: Option<bool>,  // API v3 only
+    pub only_allow_merge_if_pipeline_succeeds: Option<bool>,  // API v4 only
     pub request_access_enabled: bool,
     pub only_allow_merge_if_all_discussions_are_resolved: Option<bool>,  // FIXME: Is bool the proper type?
     pub approvals_before_merge: Option<i64>,
@@ -252,6 +320,133 @@ pub struct Project {
 pub type Projects = Vec<Project>;
 
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectHook {
+    pub id: i64,
+    pub url: String,
+    pub project_id: i64,
+    pub push_events: bool,
+    pub issues_events: bool,
+    pub confidential_issues_events: Option<bool>,
+    pub merge_requests_events: bool,
+    pub tag_push_events: bool,
+    pub note_events: bool,
+    pub build_events: Option<bool>,  // API v3 only
+    pub job_events: Option<bool>,  // API v4 only
+    pub pipeline_events: Option<bool>,
+    pub wiki_page_events: Option<bool>,
+    pub enable_ssl_verification: bool,
+    #[serde(with = "::dates::datetime")]
+    pub created_at: ::dates::DateTime,
+}
+
+pub type ProjectHooks = Vec<ProjectHook>;
+
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Branch {
+    pub name: String,
+    pub commit: ::Commit,
+    pub merged: Option<bool>,
+    pub protected: bool,
+    pub developers_can_push: bool,
+    pub developers_can_merge: bool,
+}
+
+pub type Branches = Vec<Branch>;
+
+
+/// Attributes of a project hook, set when adding or editing it.
+#[derive(Clone, Serialize)]
+struct ProjectHookInternal {
+    /// The hook URL
+    url: String,
+    /// Trigger hook on push events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    push_events: Option<bool>,
+    /// Trigger hook on issues events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issues_events: Option<bool>,
+    /// Trigger hook on confidential issues events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidential_issues_events: Option<bool>,
+    /// Trigger hook on merge requests events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_requests_events: Option<bool>,
+    /// Trigger hook on tag push events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag_push_events: Option<bool>,
+    /// Trigger hook on note events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note_events: Option<bool>,
+    /// Trigger hook on job (build with API v3) events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job_events: Option<bool>,
+    /// Trigger hook on pipeline events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pipeline_events: Option<bool>,
+    /// Trigger hook on wiki events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wiki_page_events: Option<bool>,
+    /// Do SSL verification when triggering the hook
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_ssl_verification: Option<bool>,
+    /// Secret token to validate received payloads; this will not be returned in the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+
+// Explicitly implement Debug trait for ProjectHookInternal so we can hide the token.
+impl std::fmt::Debug for ProjectHookInternal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ProjectHookInternal")
+            .field("url", &self.url)
+            .field("push_events", &self.push_events)
+            .field("issues_events", &self.issues_events)
+            .field("confidential_issues_events", &self.confidential_issues_events)
+            .field("merge_requests_events", &self.merge_requests_events)
+            .field("tag_push_events", &self.tag_push_events)
+            .field("note_events", &self.note_events)
+            .field("job_events", &self.job_events)
+            .field("pipeline_events", &self.pipeline_events)
+            .field("wiki_page_events", &self.wiki_page_events)
+            .field("enable_ssl_verification", &self.enable_ssl_verification)
+            .field("token", &self.token.as_ref().map(|_| "XXXXXXXXXXXXXXXXXXXX"))
+            .finish()
+    }
+}
+
+
+impl ProjectHookInternal {
+    fn new(url: String) -> ProjectHookInternal {
+        ProjectHookInternal {
+            url: url,
+            push_events: None,
+            issues_events: None,
+            confidential_issues_events: None,
+            merge_requests_events: None,
+            tag_push_events: None,
+            note_events: None,
+            job_events: None,
+            pipeline_events: None,
+            wiki_page_events: None,
+            enable_ssl_verification: None,
+            token: None,
+        }
+    }
+
+    /// Body of the request, with the attributes' names of `api_version`.
+    fn body(&self, api_version: ::ApiVersion) -> serde_json::Value {
+        let body = serde_json::to_value(self).unwrap();
+        match api_version {
+            ::ApiVersion::V3 => v3_attributes(body),
+            ::ApiVersion::V4 => body,
+        }
+    }
+}
+
+
 #[derive(Debug, Clone)]
 pub struct ProjectsLister<'a> {
     gl: &'a ::GitLab,
@@ -275,6 +470,17 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -303,6 +509,14 @@ impl<'a> ProjectsLister<'a> {
         owned::ProjectsLister::new(self.gl)
     }
 
+    pub fn starred(self) -> starred::ProjectsLister<'a> {
+        starred::ProjectsLister::new(self.gl)
+    }
+
+    pub fn visible(self) -> visible::ProjectsLister<'a> {
+        visible::ProjectsLister::new(self.gl)
+    }
+
     pub fn search(self, query: String) -> search::ProjectsLister<'a> {
         // assert_eq!(self, ProjectsLister::new(self.gl));
         search::ProjectsLister::new(self.gl, query)
@@ -313,6 +527,11 @@ impl<'a> ProjectsLister<'a> {
         id::ProjectsLister::new(self.gl, id)
     }
 
+    pub fn create(self, name: String) -> create::ProjectCreator<'a> {
+        // assert_eq!(self, ProjectsLister::new(self.gl));
+        create::ProjectCreator::new(self.gl, name)
+    }
+
 
 
     pub fn archived(&'a mut self, archived: bool) -> &'a mut ProjectsLister {
@@ -350,13 +569,11 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = String::from("projects");
-        if !encoded.is_empty() {
-            query.push_str("?");
-            query.push_str(&encoded);
+        match self.gl.get_api_version() {
+            ::ApiVersion::V3 => ::join_query("projects", &[&encoded]),
+            // v4 lists all the visible projects by default.
+            ::ApiVersion::V4 => ::join_query("projects", &["membership=true", &encoded]),
         }
-
-        query
     }
 }
 
@@ -396,6 +613,18 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        assert_eq!(gl.projects().build_query(), "projects?membership=true");
+        assert_eq!(gl.projects().archived(true).build_query(),
+                   "projects?membership=true&archived=true");
+    }
+
+
     #[test]
     fn build_query_archived() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
@@ -590,4 +819,20 @@ mod tests {
                     ::merge_requests::MergeRequestsLister::new(&gl, project_id));
         assert_eq!(merge_requests_lister, default_merge_requests_lister);
     }
+
+
+    #[test]
+    fn namespace_dates() {
+        use serde_json;
+
+        let json_reply = r#"{"id": 1, "name": "group", "path": "group",
+            "ldap_sync_last_update_at": "2017-02-28T14:32:00.000Z",
+            "ldap_sync_last_successful_update_at": null,
+            "deleted_at": "2017-03-01T09:00:00.000Z"}"#;
+        let namespace: ::projects::ProjectNamespace = serde_json::from_str(json_reply).unwrap();
+        assert!(namespace.ldap_sync_last_update_at.is_some());
+        assert!(namespace.ldap_sync_last_successful_update_at.is_none());
+        assert!(namespace.ldap_sync_last_sync_at.is_none());
+        assert!(namespace.deleted_at.is_some());
+    }
 }
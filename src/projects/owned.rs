@@ -10,6 +10,8 @@
 //! GET /projects/owned
 //! ```
 //!
+//! With API v4, this is `GET /projects?owned=true`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -24,6 +26,7 @@
 use serde_urlencoded;
 
 use BuildQuery;
+use Lister;
 
 use ::errors::*;
 
@@ -34,6 +37,36 @@ pub struct ProjectsLister<'a> {
     internal: ::projects::OwnedProjectListerInternal,
 }
 
+impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list(&self) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
 impl<'a> ProjectsLister<'a> {
     pub fn new(gl: &'a ::GitLab) -> ProjectsLister {
         ProjectsLister {
@@ -73,13 +106,12 @@ impl<'a> ProjectsLister<'a> {
         self
     }
 
+
     /// Commit the lister: Query GitLab and return a list of projects.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
     pub fn list(&self) -> Result<::projects::Projects> {
-        // let query = serde_urlencoded::to_string(&self);
-        let query = self.build_query();
-        debug!("query: {:?}", query);
-
-        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+        Lister::list(self)
     }
 }
 
@@ -87,11 +119,10 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = String::from("projects/owned");
-        if !encoded.is_empty() {
-            query.push_str("?");
-            query.push_str(&encoded);
-        }
+        let query = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => ::join_query("projects/owned", &[&encoded]),
+            ::ApiVersion::V4 => ::join_query("projects", &["owned=true", &encoded]),
+        };
         debug!("query: {}", query);
 
         query
@@ -120,6 +151,18 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        assert_eq!(gl.projects().owned().build_query(), "projects?owned=true");
+        assert_eq!(gl.projects().owned().archived(true).build_query(),
+                   "projects?owned=true&archived=true");
+    }
+
+
     #[test]
     fn build_query_archived() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
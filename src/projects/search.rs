@@ -10,6 +10,8 @@
 //! GET /projects/search/QUERY
 //! ```
 //!
+//! With API v4, this is `GET /projects?search=QUERY`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -52,6 +54,17 @@ impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
 
         self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
     }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
 }
 
 
@@ -82,11 +95,15 @@ impl<'a> BuildQuery for ProjectsLister<'a> {
     fn build_query(&self) -> String {
 
         let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
-        let mut query = format!("projects/search/{}", self.query);
-        if !encoded.is_empty() {
-            query.push_str("?");
-            query.push_str(&encoded);
-        }
+        let query = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => {
+                ::join_query(&format!("projects/search/{}", self.query), &[&encoded])
+            }
+            ::ApiVersion::V4 => {
+                let search = serde_urlencoded::to_string(&[("search", &self.query)]).unwrap();
+                ::join_query("projects", &[&search, &encoded])
+            }
+        };
         debug!("query: {}", query);
 
         query
@@ -114,6 +131,22 @@ mod tests {
     }
 
 
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        let query = gl.projects().search(String::from("Search Pattern")).build_query();
+        assert_eq!(query, "projects?search=Search+Pattern");
+        let query = gl.projects()
+            .search(TEST_SEARCH_QUERY.to_string())
+            .sort(::ListingSort::Asc)
+            .build_query();
+        assert_eq!(query, format!("projects?search={}&sort=asc", TEST_SEARCH_QUERY));
+    }
+
+
     #[test]
     fn build_query_order_by() {
         let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
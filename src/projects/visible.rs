@@ -8,6 +8,8 @@
 //! GET /projects/visible
 //! ```
 //!
+//! With API v4, this is `GET /projects`.
+//!
 //! Parameters:
 //!
 //! | Attribute | Type | Required | Description |
@@ -20,3 +22,320 @@
 //! | `simple` | boolean | no | Return only the ID, URL, name, and path of each project |
 //!
 //!
+
+
+use serde_urlencoded;
+
+use BuildQuery;
+use Lister;
+
+use ::errors::*;
+
+
+#[derive(Debug, Clone)]
+pub struct ProjectsLister<'a> {
+    gl: &'a ::GitLab,
+    internal: ::projects::ProjectListerInternal,
+}
+
+impl<'a> Lister<::projects::Projects> for ProjectsLister<'a> {
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list(&self) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, None, None).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a list of projects.
+    fn list_paginated(&self, page: u16, per_page: u16) -> Result<::projects::Projects> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+
+    /// Commit the lister: Query GitLab and return a page of items with pagination information.
+    fn list_page(&self,
+                 page: u16,
+                 per_page: u16)
+                 -> Result<::pagination::Page<::projects::Projects>> {
+        let query = self.build_query();
+        debug!("query: {:?}", query);
+
+        self.gl.get_page(&query, page, per_page).chain_err(|| format!("cannot get query {}", query))
+    }
+}
+
+
+impl<'a> ProjectsLister<'a> {
+    pub fn new(gl: &'a ::GitLab) -> ProjectsLister {
+        ProjectsLister {
+            gl: gl,
+            internal: ::projects::ProjectListerInternal {
+                archived: None,
+                visibility: None,
+                order_by: None,
+                sort: None,
+                search: None,
+                simple: None,
+            },
+        }
+    }
+
+    pub fn archived(&'a mut self, archived: bool) -> &'a mut ProjectsLister {
+        self.internal.archived = Some(archived);
+        self
+    }
+
+    pub fn visibility(&'a mut self, visibility: ::ListingVisibility) -> &'a mut ProjectsLister {
+        self.internal.visibility = Some(visibility);
+        self
+    }
+
+    pub fn order_by(&'a mut self, order_by: ::projects::ListingOrderBy) -> &'a mut ProjectsLister {
+        self.internal.order_by = Some(order_by);
+        self
+    }
+
+    pub fn sort(&'a mut self, sort: ::ListingSort) -> &'a mut ProjectsLister {
+        self.internal.sort = Some(sort);
+        self
+    }
+
+    pub fn search(&'a mut self, search: String) -> &'a mut ProjectsLister {
+        self.internal.search = Some(search);
+        self
+    }
+
+    pub fn simple(&'a mut self, simple: bool) -> &'a mut ProjectsLister {
+        self.internal.simple = Some(simple);
+        self
+    }
+
+    /// Commit the lister: Query GitLab and return a list of projects.
+    ///
+    /// Same as `Lister::list()`, callable without importing the trait.
+    pub fn list(&self) -> Result<::projects::Projects> {
+        Lister::list(self)
+    }
+}
+
+impl<'a> BuildQuery for ProjectsLister<'a> {
+    fn build_query(&self) -> String {
+
+        let encoded = serde_urlencoded::to_string(&self.internal).unwrap();
+        let query = match self.gl.get_api_version() {
+            ::ApiVersion::V3 => ::join_query("projects/visible", &[&encoded]),
+            ::ApiVersion::V4 => ::join_query("projects", &[&encoded]),
+        };
+        debug!("query: {}", query);
+
+        query
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use BuildQuery;
+
+
+    #[test]
+    fn build_query_default() {
+        let expected_string = "projects/visible";
+
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let projects_lister = gl.projects().visible();
+        let query = projects_lister.build_query();
+        assert_eq!(query, expected_string);
+
+        let query = gl.projects().visible().build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_v4() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX")
+            .unwrap()
+            .api_version(::ApiVersion::V4);
+
+        assert_eq!(gl.projects().visible().build_query(), "projects");
+        assert_eq!(gl.projects().visible().archived(true).build_query(),
+                   "projects?archived=true");
+    }
+
+
+    #[test]
+    fn build_query_archived() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?archived=true";
+
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.archived(true).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().archived(true).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?archived=false";
+
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.archived(false).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().archived(false).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_visibility() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?visibility=public";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.visibility(::ListingVisibility::Public).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().visibility(::ListingVisibility::Public).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?visibility=internal";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.visibility(::ListingVisibility::Internal).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().visibility(::ListingVisibility::Internal).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?visibility=private";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.visibility(::ListingVisibility::Private).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().visibility(::ListingVisibility::Private).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_order_by() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?order_by=id";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::Id).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().order_by(::projects::ListingOrderBy::Id).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?order_by=name";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::Name).build_query();
+        assert_eq!(query, expected_string);
+        let query =
+            gl.projects().visible().order_by(::projects::ListingOrderBy::Name).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?order_by=path";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::Path).build_query();
+        assert_eq!(query, expected_string);
+        let query =
+            gl.projects().visible().order_by(::projects::ListingOrderBy::Path).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?order_by=created_at";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::CreatedAt).build_query();
+        assert_eq!(query, expected_string);
+        let query =
+            gl.projects().visible().order_by(::projects::ListingOrderBy::CreatedAt).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?order_by=updated_at";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::UpdatedAt).build_query();
+        assert_eq!(query, expected_string);
+        let query =
+            gl.projects().visible().order_by(::projects::ListingOrderBy::UpdatedAt).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?order_by=last_activity_at";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.order_by(::projects::ListingOrderBy::LastActivityAt)
+            .build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects()
+            .visible()
+            .order_by(::projects::ListingOrderBy::LastActivityAt)
+            .build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_sort() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?sort=asc";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.sort(::ListingSort::Asc).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().sort(::ListingSort::Asc).build_query();
+        assert_eq!(query, expected_string);
+
+        let expected_string = "projects/visible?sort=desc";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.sort(::ListingSort::Desc).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().sort(::ListingSort::Desc).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_search() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?search=SearchPattern";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.search(String::from("SearchPattern")).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().search(String::from("SearchPattern")).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn build_query_simple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?simple=true";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.simple(true).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().simple(true).build_query();
+        assert_eq!(query, expected_string);
+    }
+
+
+    #[test]
+    fn groups_build_query_multiple() {
+        let gl = ::GitLab::new(&"localhost", "XXXXXXXXXXXXXXXXXXXX").unwrap();
+        // let gl: ::GitLab = Default::default();
+
+        let expected_string = "projects/visible?archived=true&sort=desc";
+        let mut projects_lister = gl.projects().visible();
+        let query = projects_lister.archived(true).sort(::ListingSort::Desc).build_query();
+        assert_eq!(query, expected_string);
+        let query = gl.projects().visible().archived(true).sort(::ListingSort::Desc).build_query();
+        assert_eq!(query, expected_string);
+    }
+}
@@ -0,0 +1,300 @@
+//! Client-side rate limiting
+//!
+//! https://docs.gitlab.com/ce/user/admin_area/settings/user_and_ip_rate_limits.html
+//!
+//! A `RateLimiter`, set with `GitLab::rate_limiter()`, spaces the requests sent so that they stay
+//! under a given rate. It is a token bucket: up to `burst` requests can be sent back-to-back,
+//! after which requests are let through at `requests_per_second`.
+//!
+//! It also slows down when GitLab reports that few requests are left before its own limit is
+//! hit, spreading the remaining requests until the limit is reset:
+//!
+//! | Header | Description |
+//! | ------ | ----------- |
+//! | `RateLimit-Remaining` | Number of requests left before being rate limited |
+//! | `RateLimit-Reset` | Unix timestamp at which the rate limit is reset |
+//!
+//! Clones of a `RateLimiter` share the same bucket: a single limiter can be shared between
+//! threads (as `AsyncGitLab` does) and between several `GitLab` clients.
+//!
+//! # Examples
+//!
+//! ```
+//! use gitlab_api::GitLab;
+//! use gitlab_api::rate_limit::RateLimiter;
+//!
+//! // 10 requests per second, up to 20 at once.
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .rate_limiter(RateLimiter::new(10.0, 20));
+//! ```
+
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use std;
+
+use retry::Clock;
+use transport::Response;
+
+
+/// Number of remaining requests reported by GitLab under which requests are slowed down, by
+/// default.
+pub const DEFAULT_REMAINING_THRESHOLD: u64 = 10;
+
+
+/// Token bucket limiting the rate of requests.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: u32,
+    remaining_threshold: u64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+
+#[derive(Debug)]
+struct Bucket {
+    /// Tokens left; negative when requests are waiting for tokens to be refilled.
+    tokens: f64,
+    /// When `tokens` was last updated.
+    updated_at: Option<SystemTime>,
+    /// No request should be sent before then, as asked by GitLab's headers...
+    not_before: Option<SystemTime>,
+    /// ... and the following ones should be spaced by this.
+    spacing: Duration,
+}
+
+
+impl RateLimiter {
+    /// Let through `requests_per_second` on average, and up to `burst` requests at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not a positive, finite number.
+    pub fn new(requests_per_second: f64, burst: u32) -> RateLimiter {
+        assert!(requests_per_second > 0.0 && requests_per_second.is_finite(),
+                "invalid rate limit: {} requests per second",
+                requests_per_second);
+        let burst = std::cmp::max(burst, 1);
+        RateLimiter {
+            requests_per_second: requests_per_second,
+            burst: burst,
+            remaining_threshold: DEFAULT_REMAINING_THRESHOLD,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(burst),
+                updated_at: None,
+                not_before: None,
+                spacing: Duration::from_secs(0),
+            })),
+        }
+    }
+
+    /// Slow down once GitLab reports (`RateLimit-Remaining`) `remaining_threshold` requests or
+    /// less before being rate limited. `0` only waits for the reset once no request is left.
+    pub fn remaining_threshold(mut self, remaining_threshold: u64) -> Self {
+        self.remaining_threshold = remaining_threshold;
+        self
+    }
+
+    /// How long to wait before sending a request, reserving a token for it.
+    pub fn reserve(&self, now: SystemTime) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+
+        if let Some(updated_at) = bucket.updated_at {
+            let elapsed = now.duration_since(updated_at).unwrap_or_else(|_| Duration::from_secs(0));
+            let tokens = bucket.tokens + ::duration_as_secs(elapsed) * self.requests_per_second;
+            bucket.tokens = tokens.min(f64::from(self.burst));
+        }
+        bucket.updated_at = Some(now);
+        bucket.tokens -= 1.0;
+
+        let mut wait = if bucket.tokens < 0.0 {
+            ::duration_from_secs(-bucket.tokens / self.requests_per_second)
+        } else {
+            Duration::from_secs(0)
+        };
+        if let Some(not_before) = bucket.not_before {
+            if let Ok(until) = not_before.duration_since(now) {
+                wait = std::cmp::max(wait, until);
+            }
+            bucket.not_before = Some(now + wait + bucket.spacing);
+        }
+
+        wait
+    }
+
+    /// Wait, using `clock`, until a request can be sent.
+    pub fn acquire(&self, clock: &Clock) {
+        let wait = self.reserve(clock.now());
+        if wait > Duration::from_secs(0) {
+            info!("rate limited: waiting {:?}", wait);
+            clock.sleep(wait);
+        }
+    }
+
+    /// Take into account the rate limit headers of a response received at `now`.
+    pub fn observe(&self, response: &Response, now: SystemTime) {
+        let remaining: Option<u64> = response.header("RateLimit-Remaining")
+            .and_then(|remaining| remaining.trim().parse().ok());
+        let reset: Option<i64> = response.header("RateLimit-Reset")
+            .and_then(|reset| reset.trim().parse().ok());
+
+        let mut bucket = self.bucket.lock().unwrap();
+        match (remaining, reset) {
+            (Some(remaining), Some(reset)) if remaining <= self.remaining_threshold => {
+                // Spread the remaining requests until the reset.
+                let until_reset = ::until_timestamp(reset, now);
+                let spacing = divide(until_reset, remaining.saturating_add(1));
+                debug!("{} requests remaining for {:?}, spacing requests by {:?}",
+                       remaining,
+                       until_reset,
+                       spacing);
+                bucket.not_before = Some(now + spacing);
+                bucket.spacing = spacing;
+            }
+            _ => bucket.not_before = None,
+        }
+    }
+}
+
+
+/// `duration` divided by `divisor` (not zero), without `Duration`'s division by a `u32`.
+fn divide(duration: Duration, divisor: u64) -> Duration {
+    let secs = duration.as_secs();
+    // Less than 2 seconds, carried over by `Duration::new()`.
+    let nanos = (secs % divisor)
+        .saturating_mul(1_000_000_000)
+        .saturating_add(u64::from(duration.subsec_nanos())) / divisor;
+    Duration::new(secs / divisor + nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use rate_limit::*;
+    use retry::{Clock, MockClock};
+    use transport::Response;
+
+
+    #[test]
+    fn burst_then_rate() {
+        let limiter = RateLimiter::new(2.0, 3);
+        let clock = MockClock::default();
+
+        for _ in 0..5 {
+            limiter.acquire(&clock);
+        }
+        assert_eq!(clock.sleeps(),
+                   vec![Duration::from_millis(500), Duration::from_millis(500)]);
+    }
+
+
+    #[test]
+    fn invalid_rate() {
+        use std;
+
+        for &rate in &[0.0, -1.0, std::f64::NAN, std::f64::INFINITY] {
+            assert!(std::panic::catch_unwind(|| RateLimiter::new(rate, 1)).is_err(),
+                    "rate {} accepted",
+                    rate);
+        }
+        RateLimiter::new(0.5, 1);
+    }
+
+
+    #[test]
+    fn refill() {
+        let limiter = RateLimiter::new(1.0, 2);
+        let now = UNIX_EPOCH;
+
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(now), Duration::from_secs(1));
+
+        // Never refilled above the burst size.
+        let later = now + Duration::from_secs(60);
+        assert_eq!(limiter.reserve(later), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(later), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(later), Duration::from_secs(1));
+    }
+
+
+    #[test]
+    fn shared_between_clones() {
+        let limiter = RateLimiter::new(1.0, 1);
+        let shared = limiter.clone();
+
+        assert_eq!(limiter.reserve(UNIX_EPOCH), Duration::from_secs(0));
+        assert_eq!(shared.reserve(UNIX_EPOCH), Duration::from_secs(1));
+    }
+
+
+    #[test]
+    fn slow_down_on_remaining() {
+        let limiter = RateLimiter::new(100.0, 100).remaining_threshold(3);
+        let now = UNIX_EPOCH + Duration::from_secs(1_488_292_320);
+
+        // Plenty left
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "50")
+            .with_header("RateLimit-Reset", "1488292380");
+        limiter.observe(&response, now);
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+
+        // 3 requests left for a minute
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "3")
+            .with_header("RateLimit-Reset", "1488292380");
+        limiter.observe(&response, now);
+        assert_eq!(limiter.reserve(now), Duration::from_secs(15));
+        assert_eq!(limiter.reserve(now), Duration::from_secs(30));
+
+        // None left
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "0")
+            .with_header("RateLimit-Reset", "1488292380");
+        limiter.observe(&response, now);
+        assert_eq!(limiter.reserve(now), Duration::from_secs(60));
+
+        // Reset
+        limiter.observe(&Response::new(200, ""), now);
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+    }
+
+
+    #[test]
+    fn many_remaining() {
+        let limiter = RateLimiter::new(100.0, 100).remaining_threshold(std::u64::MAX);
+        let now = UNIX_EPOCH + Duration::from_secs(1_488_292_320);
+
+        // More remaining requests than a `u32` can count: spaced by 60s / 2^32.
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "4294967295")
+            .with_header("RateLimit-Reset", "1488292380");
+        limiter.observe(&response, now);
+        assert_eq!(limiter.reserve(now), Duration::new(0, 13));
+
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "18446744073709551615")
+            .with_header("RateLimit-Reset", "1488292380");
+        limiter.observe(&response, now);
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+    }
+
+
+    #[test]
+    fn acquire_waits_for_reset() {
+        let limiter = RateLimiter::new(100.0, 100);
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_488_292_320));
+
+        let response = Response::new(200, "")
+            .with_header("RateLimit-Remaining", "0")
+            .with_header("RateLimit-Reset", "1488292330");
+        limiter.observe(&response, clock.now());
+        limiter.acquire(&clock);
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(10)]);
+    }
+}
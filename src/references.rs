@@ -0,0 +1,492 @@
+//! Parsing GitLab web URLs and references
+//!
+//! https://docs.gitlab.com/ce/user/markdown.html#special-gitlab-references
+//!
+//! Links pasted from the web UI and the short references used in GitLab Flavored Markdown are
+//! parsed into a `Reference`:
+//!
+//! | Input | Reference |
+//! | ----- | --------- |
+//! | `https://gitlab.example.com/group/project` | `Project` |
+//! | `https://gitlab.example.com/group/sub/project/issues/42` | `Issue` |
+//! | `https://gitlab.example.com/group/project/merge_requests/17` | `MergeRequest` |
+//! | `https://gitlab.example.com/group/project/milestones/3` | `Milestone`, by iid |
+//! | `https://gitlab.example.com/group/project/commit/7a3f1c9` | `Commit` |
+//! | `#42`, `group/project#42` | `Issue` |
+//! | `!17`, `group/project!17` | `MergeRequest` |
+//! | `%3`, `%v1.0`, `%"Release 1.0"`, `group/project%v1.0` | `Milestone`, by iid or title |
+//! | `~3`, `~bug`, `~"help wanted"`, `group/project~bug` | `Label`, by id or name |
+//! | `7a3f1c9`, `group/project@7a3f1c9` | `Commit` |
+//! | `group/project` | `Project` |
+//!
+//! URLs of the newer `/-/` form (`group/project/-/issues/42`) are understood too. A web URL is
+//! parsed into a `Reference::Url`, which keeps the host (and port) of the URL along with what it
+//! points to.
+//!
+//! `GitLab::resolve()` fetches the project, issue or merge request a reference points to.
+//!
+//! # Examples
+//!
+//! ```
+//! use gitlab_api::references::{Reference, NameOrId};
+//!
+//! let reference: Reference = "https://gitlab.example.com/group/sub/project/issues/42"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(reference.host(), Some("gitlab.example.com"));
+//! assert_eq!(reference,
+//!            Reference::Url {
+//!                host: String::from("gitlab.example.com"),
+//!                reference: Box::new(Reference::Issue {
+//!                    project: Some(String::from("group/sub/project")),
+//!                    iid: 42,
+//!                }),
+//!            });
+//!
+//! let reference: Reference = "group/project~\"help wanted\"".parse().unwrap();
+//! assert_eq!(reference,
+//!            Reference::Label {
+//!                project: Some(String::from("group/project")),
+//!                label: NameOrId::Name(String::from("help wanted")),
+//!            });
+//! ```
+
+
+use std::fmt;
+use std::str::FromStr;
+
+use url;
+use url::percent_encoding::percent_decode;
+
+use ::errors::*;
+
+
+/// A milestone or a label, referenced by its `iid` (milestones), `id` (labels) or name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameOrId {
+    Id(i64),
+    Name(String),
+}
+
+
+/// Something a GitLab web URL or reference points to.
+///
+/// `project` is the full path of the project (`"group/sub/project"`), or `None` when the reference
+/// is relative to the project it appears in (for example `#42` in an issue description).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// A web URL, pointing to `reference` on `host` (followed by the port, unless it is the
+    /// default one of the scheme).
+    Url { host: String, reference: Box<Reference> },
+    Project(String),
+    Issue { project: Option<String>, iid: i64 },
+    MergeRequest { project: Option<String>, iid: i64 },
+    Milestone { project: Option<String>, milestone: NameOrId },
+    Label { project: Option<String>, label: NameOrId },
+    Commit { project: Option<String>, sha: String },
+}
+
+
+/// What `GitLab::resolve()` fetched.
+#[derive(Debug)]
+pub enum Resolved {
+    Project(Box<::projects::Project>),
+    Issue(::issues::Issue),
+    MergeRequest(::merge_requests::MergeRequest),
+}
+
+
+impl Reference {
+    /// Parse a web URL (`https://...`) or a reference (`group/project#42`).
+    pub fn parse(reference: &str) -> Result<Reference> {
+        let reference = reference.trim();
+        let parsed = if reference.starts_with("http://") || reference.starts_with("https://") {
+            parse_url(reference)
+        } else {
+            parse_reference(reference)
+        };
+
+        parsed.ok_or_else(|| ErrorKind::InvalidReference(reference.to_string()).into())
+    }
+
+    /// Full path of the project the reference points to or into, if known.
+    pub fn project(&self) -> Option<&str> {
+        match *self {
+            Reference::Url { ref reference, .. } => reference.project(),
+            Reference::Project(ref project) => Some(project),
+            Reference::Issue { ref project, .. } |
+            Reference::MergeRequest { ref project, .. } |
+            Reference::Milestone { ref project, .. } |
+            Reference::Label { ref project, .. } |
+            Reference::Commit { ref project, .. } => project.as_ref().map(String::as_str),
+        }
+    }
+
+    /// Host (and port) of the GitLab instance a web URL points to, `None` for other references.
+    pub fn host(&self) -> Option<&str> {
+        match *self {
+            Reference::Url { ref host, .. } => Some(host),
+            _ => None,
+        }
+    }
+}
+
+
+impl FromStr for Reference {
+    type Err = Error;
+
+    fn from_str(reference: &str) -> Result<Reference> {
+        Reference::parse(reference)
+    }
+}
+
+
+impl fmt::Display for NameOrId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NameOrId::Id(id) => write!(f, "{}", id),
+            NameOrId::Name(ref name) if is_word(name) && !is_number(name) => write!(f, "{}", name),
+            NameOrId::Name(ref name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+
+/// Format as a GitLab reference (`group/project#42`), which can be parsed back. The host of web
+/// URLs is left out.
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let project = self.project().unwrap_or("");
+        match *self {
+            Reference::Url { ref reference, .. } => write!(f, "{}", reference),
+            Reference::Project(_) => write!(f, "{}", project),
+            Reference::Issue { iid, .. } => write!(f, "{}#{}", project, iid),
+            Reference::MergeRequest { iid, .. } => write!(f, "{}!{}", project, iid),
+            Reference::Milestone { ref milestone, .. } => write!(f, "{}%{}", project, milestone),
+            Reference::Label { ref label, .. } => write!(f, "{}~{}", project, label),
+            Reference::Commit { project: None, ref sha } => write!(f, "{}", sha),
+            Reference::Commit { ref sha, .. } => write!(f, "{}@{}", project, sha),
+        }
+    }
+}
+
+
+/// Parse a web URL: `https://gitlab.example.com/<project path>[/-]/<kind>/<id>[/...]`.
+fn parse_url(reference: &str) -> Option<Reference> {
+    let url = match url::Url::parse(reference) {
+        Ok(url) => url,
+        Err(_) => return None,
+    };
+    let host = match ::host_and_port(&url) {
+        Some(host) => host,
+        None => return None,
+    };
+    let segments: Vec<String> = match url.path_segments() {
+        Some(segments) => {
+            segments.filter(|segment| !segment.is_empty())
+                .map(|segment| percent_decode(segment.as_bytes()).decode_utf8_lossy().into_owned())
+                .collect()
+        }
+        None => return None,
+    };
+
+    // The project path ends before `-` or before the first known route; a project path has at
+    // least a namespace and a name.
+    let end = segments.iter()
+        .enumerate()
+        .skip(1)
+        .find(|&(_, segment)| segment == "-" || ROUTES.contains(&segment.as_str()))
+        .map(|(index, _)| index)
+        .unwrap_or(segments.len());
+    if end < 2 {
+        return None;
+    }
+    let project = segments[..end].join("/");
+    let project = project.trim_right_matches(".git").to_string();
+    if !is_project_path(&project) {
+        return None;
+    }
+
+    let route: Vec<&str> = segments[end..]
+        .iter()
+        .map(String::as_str)
+        .skip_while(|&segment| segment == "-")
+        .collect();
+    let project_ref = Some(project.clone());
+    let reference = match (route.first().cloned(), route.get(1).cloned()) {
+        (Some("issues"), Some(iid)) => {
+            iid.parse().ok().map(|iid| Reference::Issue { project: project_ref, iid: iid })
+        }
+        (Some("merge_requests"), Some(iid)) => {
+            iid.parse().ok().map(|iid| Reference::MergeRequest { project: project_ref, iid: iid })
+        }
+        (Some("milestones"), Some(iid)) => {
+            iid.parse().ok().map(|iid| {
+                Reference::Milestone {
+                    project: project_ref,
+                    milestone: NameOrId::Id(iid),
+                }
+            })
+        }
+        (Some("commit"), Some(sha)) if is_sha(sha) => {
+            Some(Reference::Commit { project: project_ref, sha: sha.to_string() })
+        }
+        (Some("commit"), _) => None,
+        _ => Some(Reference::Project(project)),
+    };
+
+    reference.map(|reference| Reference::Url { host: host, reference: Box::new(reference) })
+}
+
+
+/// Routes following the project path in web URLs.
+const ROUTES: &'static [&'static str] = &["issues",
+                          "merge_requests",
+                          "milestones",
+                          "commit",
+                          "commits",
+                          "tree",
+                          "blob",
+                          "labels",
+                          "pipelines",
+                          "wikis"];
+
+
+/// Parse a reference: `[<project path>]<sigil><id>`, a commit SHA or a project path.
+fn parse_reference(reference: &str) -> Option<Reference> {
+    let sigil = match reference.find(|c| "#!%~@".contains(c)) {
+        Some(sigil) => sigil,
+        None if is_sha(reference) => {
+            return Some(Reference::Commit { project: None, sha: reference.to_string() });
+        }
+        None if reference.contains('/') && is_project_path(reference) => {
+            return Some(Reference::Project(reference.to_string()));
+        }
+        None => return None,
+    };
+
+    let project = match &reference[..sigil] {
+        "" => None,
+        project if is_project_path(project) => Some(project.to_string()),
+        _ => return None,
+    };
+    let id = &reference[sigil + 1..];
+
+    match &reference[sigil..sigil + 1] {
+        "#" => parse_iid(id).map(|iid| Reference::Issue { project: project, iid: iid }),
+        "!" => parse_iid(id).map(|iid| Reference::MergeRequest { project: project, iid: iid }),
+        "%" => {
+            parse_name_or_id(id).map(|milestone| {
+                Reference::Milestone {
+                    project: project,
+                    milestone: milestone,
+                }
+            })
+        }
+        "~" => {
+            parse_name_or_id(id).map(|label| Reference::Label { project: project, label: label })
+        }
+        _ if project.is_some() && is_sha(id) => {
+            Some(Reference::Commit { project: project, sha: id.to_string() })
+        }
+        _ => None,
+    }
+}
+
+
+fn parse_iid(iid: &str) -> Option<i64> {
+    if is_number(iid) { iid.parse().ok() } else { None }
+}
+
+
+/// `3`, `name` or `"name with spaces"`.
+fn parse_name_or_id(id: &str) -> Option<NameOrId> {
+    if id.len() > 2 && id.starts_with('"') && id.ends_with('"') {
+        let name = &id[1..id.len() - 1];
+        if name.contains('"') {
+            return None;
+        }
+        Some(NameOrId::Name(name.to_string()))
+    } else if is_number(id) {
+        id.parse().ok().map(NameOrId::Id)
+    } else if is_word(id) {
+        Some(NameOrId::Name(id.to_string()))
+    } else {
+        None
+    }
+}
+
+
+fn is_number(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_digit(10))
+}
+
+
+/// Name which can be referenced without quotes.
+fn is_word(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || "_-.:/&?".contains(c))
+}
+
+
+/// Abbreviated (at least 7 characters) or full commit SHA.
+fn is_sha(sha: &str) -> bool {
+    sha.len() >= 7 && sha.len() <= 40 && sha.chars().all(|c| c.is_digit(16))
+}
+
+
+fn is_project_path(path: &str) -> bool {
+    !path.is_empty() &&
+    path.split('/').all(|part| {
+        !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || "_-.".contains(c))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use references::*;
+
+
+    fn parse(reference: &str) -> Reference {
+        reference.parse().unwrap_or_else(|e| panic!("cannot parse '{}': {}", reference, e))
+    }
+
+
+    fn project(path: &str) -> Option<String> {
+        Some(path.to_string())
+    }
+
+
+    fn url(host: &str, reference: Reference) -> Reference {
+        Reference::Url { host: host.to_string(), reference: Box::new(reference) }
+    }
+
+
+    #[test]
+    fn web_urls() {
+        let host = "gitlab.example.com";
+        assert_eq!(parse("https://gitlab.example.com/group/project"),
+                   url(host, Reference::Project(String::from("group/project"))));
+        assert_eq!(parse("https://gitlab.example.com/group/sub/project.git"),
+                   url(host, Reference::Project(String::from("group/sub/project"))));
+        assert_eq!(parse("https://gitlab.example.com/group/project/tree/master/src"),
+                   url(host, Reference::Project(String::from("group/project"))));
+        assert_eq!(parse("https://gitlab.example.com/group/sub/project/issues/42"),
+                   url(host, Reference::Issue { project: project("group/sub/project"), iid: 42 }));
+        assert_eq!(parse("https://gitlab.example.com/group/project/issues/42#note_1234"),
+                   url(host, Reference::Issue { project: project("group/project"), iid: 42 }));
+        assert_eq!(parse("http://localhost:8080/group/project/merge_requests/17/diffs"),
+                   url("localhost:8080",
+                       Reference::MergeRequest { project: project("group/project"), iid: 17 }));
+        assert_eq!(parse("https://gitlab.example.com/group/project/-/merge_requests/17"),
+                   url(host,
+                       Reference::MergeRequest { project: project("group/project"), iid: 17 }));
+        assert_eq!(parse("https://gitlab.example.com/group/project/milestones/3"),
+                   url(host,
+                       Reference::Milestone {
+                           project: project("group/project"),
+                           milestone: NameOrId::Id(3),
+                       }));
+        assert_eq!(parse("https://gitlab.example.com/group/project/commit/7a3f1c9e"),
+                   url(host,
+                       Reference::Commit {
+                           project: project("group/project"),
+                           sha: String::from("7a3f1c9e"),
+                       }));
+    }
+
+
+    #[test]
+    fn web_url_host() {
+        let reference = parse("https://gitlab.example.com:443/group/project/issues/42");
+        assert_eq!(reference.host(), Some("gitlab.example.com"));
+        assert_eq!(reference.project(), Some("group/project"));
+        assert_eq!(reference.to_string(), "group/project#42");
+        assert_eq!(parse("group/project#42").host(), None);
+    }
+
+
+    #[test]
+    fn invalid_web_urls() {
+        for url in &["https://gitlab.example.com/",
+                     "https://gitlab.example.com/group",
+                     "https://gitlab.example.com/group/issues/42",
+                     "https://gitlab.example.com/group/project/issues/new",
+                     "https://gitlab.example.com/group/project/commit/master"] {
+            match url.parse::<Reference>() {
+                Err(Error(ErrorKind::InvalidReference(ref reference), _)) => {
+                    assert_eq!(reference, url)
+                }
+                result => panic!("unexpected result for '{}': {:?}", url, result),
+            }
+        }
+    }
+
+
+    #[test]
+    fn references() {
+        assert_eq!(parse("#42"), Reference::Issue { project: None, iid: 42 });
+        assert_eq!(parse("group/sub/project#42"),
+                   Reference::Issue { project: project("group/sub/project"), iid: 42 });
+        assert_eq!(parse("!17"), Reference::MergeRequest { project: None, iid: 17 });
+        assert_eq!(parse("group/project!17"),
+                   Reference::MergeRequest { project: project("group/project"), iid: 17 });
+        assert_eq!(parse("%3"),
+                   Reference::Milestone { project: None, milestone: NameOrId::Id(3) });
+        assert_eq!(parse("group/project%v1.0"),
+                   Reference::Milestone {
+                       project: project("group/project"),
+                       milestone: NameOrId::Name(String::from("v1.0")),
+                   });
+        assert_eq!(parse("%\"Release 1.0\""),
+                   Reference::Milestone {
+                       project: None,
+                       milestone: NameOrId::Name(String::from("Release 1.0")),
+                   });
+        assert_eq!(parse("~bug"),
+                   Reference::Label { project: None, label: NameOrId::Name(String::from("bug")) });
+        assert_eq!(parse("~\"help wanted\""),
+                   Reference::Label {
+                       project: None,
+                       label: NameOrId::Name(String::from("help wanted")),
+                   });
+        assert_eq!(parse("7a3f1c9"),
+                   Reference::Commit { project: None, sha: String::from("7a3f1c9") });
+        assert_eq!(parse("group/project@7a3f1c9"),
+                   Reference::Commit {
+                       project: project("group/project"),
+                       sha: String::from("7a3f1c9"),
+                   });
+        assert_eq!(parse(" group/project "),
+                   Reference::Project(String::from("group/project")));
+    }
+
+
+    #[test]
+    fn invalid_references() {
+        for reference in &["", "42", "project", "#", "#abc", "!-1", "~", "~\"\"", "~two words",
+                           "@7a3f1c9", "group/project@master", "group project#42", "group//p#4"] {
+            assert!(reference.parse::<Reference>().is_err(),
+                    "'{}' should not be parsed",
+                    reference);
+        }
+    }
+
+
+    #[test]
+    fn display_round_trip() {
+        for reference in &["#42", "group/project!17", "%3", "group/project%v1.0",
+                           "%\"Release 1\"", "~bug", "~\"help wanted\"", "~\"42\"", "7a3f1c9",
+                           "group/project@7a3f1c9", "group/sub/project"] {
+            assert_eq!(parse(reference).to_string(), *reference);
+        }
+    }
+
+
+    #[test]
+    fn project_path() {
+        assert_eq!(parse("group/project#42").project(), Some("group/project"));
+        assert_eq!(parse("#42").project(), None);
+        assert_eq!(parse("group/project").project(), Some("group/project"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Retrying failed requests
+//!
+//! GitLab (or a proxy in front of it) sometimes answers with a transient error: `429 Too Many
+//! Requests` when rate limiting kicks in, `502 Bad Gateway`, `503 Service Unavailable` or
+//! `504 Gateway Timeout` while it restarts. A `RetryPolicy`, set with `GitLab::retry_policy()`,
+//! sends such requests again after waiting a bit.
+//!
+//! The delay before each retry grows exponentially (`base_delay`, then twice that, four times,
+//! etc., up to `max_delay`) and is randomly shortened by up to `jitter` (a fraction of the delay)
+//! so that concurrent clients do not retry in lockstep. When the response tells how long to wait,
+//! that is honored instead:
+//!
+//! | Header | Description |
+//! | ------ | ----------- |
+//! | `Retry-After` | Number of seconds to wait, or the date after which to retry |
+//! | `RateLimit-Reset` | Unix timestamp at which the rate limit is reset |
+//!
+//! By default, `GitLab` does not retry. Time is read and waited for through a `Clock`, which can
+//! be replaced (see `MockClock`) to test code built on top of this crate without sleeping.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use gitlab_api::GitLab;
+//! use gitlab_api::retry::RetryPolicy;
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .retry_policy(RetryPolicy::new()
+//!         .max_attempts(5)
+//!         .base_delay(Duration::from_millis(500))
+//!         .statuses(&[429, 503]));
+//! ```
+
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std;
+
+use hyper;
+use rand;
+
+use transport::{Method, Response};
+
+
+/// Reads the current time and waits.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+    fn sleep(&self, duration: Duration);
+}
+
+
+/// The system's clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+
+/// Clock which does not wait: sleeping moves its time forward.
+///
+/// Every sleep is recorded and can be inspected with `sleeps()`. Clones share the same state, so
+/// a clone can be handed to `GitLab::clock()` while the original is kept around for assertions.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+
+impl MockClock {
+    /// A clock starting at `now`.
+    pub fn new(now: SystemTime) -> MockClock {
+        MockClock {
+            now: Arc::new(Mutex::new(now)),
+            sleeps: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// All the sleeps so far, oldest first.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+}
+
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        MockClock::new(UNIX_EPOCH)
+    }
+}
+
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+        self.sleeps.lock().unwrap().push(duration);
+    }
+}
+
+
+/// When and how to retry a failed request.
+///
+/// `RetryPolicy::new()` (or `default()`) allows 3 attempts of idempotent requests (`GET`, `PUT`
+/// and `DELETE`) failing with `429`, `502`, `503` or `504`, or because of the transport (the
+/// connection was reset, a timeout elapsed, etc.), waiting 1 second before the first retry, up to
+/// 1 minute, with a jitter of 0.5.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: f64,
+    methods: Vec<Method>,
+    statuses: Vec<u16>,
+}
+
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.5,
+            methods: vec![Method::Get, Method::Put, Method::Delete],
+            statuses: vec![429, 502, 503, 504],
+        }
+    }
+}
+
+
+impl RetryPolicy {
+    pub fn new() -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// A policy sending each request only once.
+    pub fn never() -> RetryPolicy {
+        RetryPolicy::default().max_attempts(1)
+    }
+
+    /// Maximum number of times a request is sent, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Delay before the first retry, doubled for each following one.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound of the exponentially growing delay. Delays asked for by the server are not
+    /// bounded.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Fraction of the delay (between 0 and 1) randomly removed from it. `0` disables jitter.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.max(0.0).min(1.0);
+        self
+    }
+
+    /// Methods of the requests which can be retried.
+    pub fn methods(mut self, methods: &[Method]) -> Self {
+        self.methods = methods.to_vec();
+        self
+    }
+
+    /// Status codes of the responses after which the request is retried.
+    pub fn statuses(mut self, statuses: &[u16]) -> Self {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// How long to wait before sending again a request which failed on its `attempt`th
+    /// attempt (starting at 1, `0` is taken as `1`), or `None` if it should not be retried.
+    ///
+    /// `response` is `None` if the request failed because of the transport.
+    pub fn retry_delay(&self,
+                       method: Method,
+                       attempt: u32,
+                       response: Option<&Response>,
+                       now: SystemTime)
+                       -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.methods.contains(&method) {
+            return None;
+        }
+
+        match response {
+            Some(response) if !self.statuses.contains(&response.status) => None,
+            Some(response) => {
+                Some(server_delay(response, now).unwrap_or_else(|| self.backoff_delay(attempt)))
+            }
+            None => Some(self.backoff_delay(attempt)),
+        }
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        // Any delay is past `max_delay` long before 2^64 times `base_delay`.
+        let factor = 2f64.powi(std::cmp::min(attempt.saturating_sub(1), 64) as i32);
+        let delay = ::duration_as_secs(self.base_delay) * factor;
+        let delay = delay.min(::duration_as_secs(self.max_delay));
+
+        let jitter = self.jitter * rand::random::<f64>();
+        ::duration_from_secs(delay - delay * jitter)
+    }
+}
+
+
+/// Delay asked for by the server through `Retry-After` or `RateLimit-Reset`, if any.
+fn server_delay(response: &Response, now: SystemTime) -> Option<Duration> {
+    if let Some(retry_after) = response.header("Retry-After").map(str::trim) {
+        if let Ok(seconds) = retry_after.parse() {
+            return Some(Duration::from_secs(seconds));
+        }
+        if let Ok(date) = retry_after.parse::<hyper::header::HttpDate>() {
+            let timestamp = date.0.to_timespec().sec;
+            return Some(::until_timestamp(timestamp, now));
+        }
+    }
+
+    response.header("RateLimit-Reset")
+        .and_then(|reset| reset.trim().parse().ok())
+        .map(|timestamp| ::until_timestamp(timestamp, now))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use retry::*;
+    use transport::{Method, Response};
+
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new().max_attempts(5).jitter(0.0)
+    }
+
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = policy().max_delay(Duration::from_secs(5));
+        let response = Response::new(503, "");
+
+        let delays: Vec<_> = (1..5)
+            .map(|attempt| policy.retry_delay(Method::Get, attempt, Some(&response), UNIX_EPOCH))
+            .collect();
+        assert_eq!(delays,
+                   vec![Some(Duration::from_secs(1)),
+                        Some(Duration::from_secs(2)),
+                        Some(Duration::from_secs(4)),
+                        Some(Duration::from_secs(5))]);
+        assert_eq!(policy.retry_delay(Method::Get, 5, Some(&response), UNIX_EPOCH), None);
+    }
+
+
+    #[test]
+    fn backoff_bounds() {
+        use std;
+
+        let policy = policy().max_attempts(std::u32::MAX).max_delay(Duration::from_secs(5));
+
+        assert_eq!(policy.retry_delay(Method::Get, 0, None, UNIX_EPOCH),
+                   Some(Duration::from_secs(1)));
+        assert_eq!(policy.retry_delay(Method::Get, std::u32::MAX - 1, None, UNIX_EPOCH),
+                   Some(Duration::from_secs(5)));
+    }
+
+
+    #[test]
+    fn jitter_shortens_delay() {
+        let policy = RetryPolicy::new().jitter(0.5);
+        let response = Response::new(502, "");
+
+        for _ in 0..100 {
+            let delay = policy.retry_delay(Method::Get, 2, Some(&response), UNIX_EPOCH).unwrap();
+            assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+        }
+    }
+
+
+    #[test]
+    fn retryable_methods_and_statuses() {
+        let policy = policy();
+        let unavailable = Response::new(503, "");
+
+        assert!(policy.retry_delay(Method::Post, 1, Some(&unavailable), UNIX_EPOCH).is_none());
+        assert!(policy.retry_delay(Method::Delete, 1, Some(&unavailable), UNIX_EPOCH).is_some());
+        assert!(policy.retry_delay(Method::Get, 1, Some(&Response::new(500, "")), UNIX_EPOCH)
+            .is_none());
+        assert!(policy.retry_delay(Method::Get, 1, None, UNIX_EPOCH).is_some());
+
+        let policy = policy.methods(&[Method::Post]).statuses(&[500]);
+        assert!(policy.retry_delay(Method::Post, 1, Some(&Response::new(500, "")), UNIX_EPOCH)
+            .is_some());
+        assert!(policy.retry_delay(Method::Get, 1, Some(&unavailable), UNIX_EPOCH).is_none());
+    }
+
+
+    #[test]
+    fn never() {
+        let policy = RetryPolicy::never();
+        assert!(policy.retry_delay(Method::Get, 1, Some(&Response::new(503, "")), UNIX_EPOCH)
+            .is_none());
+    }
+
+
+    #[test]
+    fn retry_after_seconds() {
+        let response = Response::new(429, "").with_header("Retry-After", "30");
+        assert_eq!(policy().retry_delay(Method::Get, 1, Some(&response), UNIX_EPOCH),
+                   Some(Duration::from_secs(30)));
+    }
+
+
+    #[test]
+    fn retry_after_date() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_488_292_320);  // 2017-02-28T14:32:00Z
+        let response = Response::new(503, "")
+            .with_header("Retry-After", "Tue, 28 Feb 2017 14:33:30 GMT");
+        assert_eq!(policy().retry_delay(Method::Get, 1, Some(&response), now),
+                   Some(Duration::from_secs(90)));
+    }
+
+
+    #[test]
+    fn rate_limit_reset() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_488_292_320);
+        let response = Response::new(429, "").with_header("RateLimit-Reset", "1488292335");
+        assert_eq!(policy().retry_delay(Method::Get, 1, Some(&response), now),
+                   Some(Duration::from_secs(15)));
+
+        // Already reset
+        let response = Response::new(429, "").with_header("RateLimit-Reset", "1488292300");
+        assert_eq!(policy().retry_delay(Method::Get, 1, Some(&response), now),
+                   Some(Duration::from_secs(0)));
+    }
+
+
+    #[test]
+    fn mock_clock() {
+        let clock = MockClock::default();
+        let shared = clock.clone();
+        shared.sleep(Duration::from_secs(2));
+        shared.sleep(Duration::from_secs(3));
+
+        assert_eq!(clock.now(), UNIX_EPOCH + Duration::from_secs(5));
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(2), Duration::from_secs(3)]);
+    }
+}
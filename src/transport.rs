@@ -0,0 +1,572 @@
+//! HTTP transport layer
+//!
+//! `GitLab` does not talk to the network directly: every request goes through an object
+//! implementing the `HttpTransport` trait. The default transport, `HyperTransport`, is built on
+//! top of a `hyper::Client`. `MockTransport` replays canned responses from memory, allowing code
+//! built on top of the listers to be tested without a live GitLab server.
+//!
+//! # Examples
+//!
+//! ```
+//! use gitlab_api::GitLab;
+//! use gitlab_api::transport::{MockTransport, Response};
+//!
+//! let mock = MockTransport::new();
+//! mock.push_response(Response::new(200, r#"{"version": "8.16.4", "revision": "b7e4c2d"}"#));
+//!
+//! let gl = GitLab::new("gitlab.example.com", "XXXXXXXXXXXXXXXXXXXX")
+//!     .unwrap()
+//!     .transport(mock.clone());
+//!
+//! let version = gl.version().unwrap();
+//! assert_eq!(version.version, "8.16.4");
+//! assert_eq!(mock.requests().len(), 1);
+//! ```
+
+
+use std::io::Read;  // Trait providing read_to_string()
+use std::cmp;
+use std::collections::VecDeque;
+use std::io;
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std;
+
+use hyper;
+
+use ::errors::*;
+
+
+/// HTTP method of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match *self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        })
+    }
+}
+
+
+/// Header carrying the private token of the user.
+pub const PRIVATE_TOKEN_HEADER: &'static str = "PRIVATE-TOKEN";
+
+/// Header carrying the token of a CI job.
+pub const JOB_TOKEN_HEADER: &'static str = "JOB-TOKEN";
+
+/// Headers whose value is a secret, hidden from the `Debug` output of a `Request`.
+const SECRET_HEADERS: &'static [&'static str] = &[PRIVATE_TOKEN_HEADER,
+                                                 JOB_TOKEN_HEADER,
+                                                 "Authorization"];
+
+
+/// A request, as handed to a transport.
+///
+/// Its `Debug` implementation hides the value of the headers carrying secrets, so that requests
+/// can be logged safely.
+#[derive(Clone, PartialEq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+
+impl Request {
+    pub fn new(method: Method, url: &str) -> Request {
+        Request {
+            method: method,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Value of the first header named `name` (case insensitive), if any.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+
+impl std::fmt::Debug for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let headers: Vec<(&str, &str)> = self.headers
+            .iter()
+            .map(|&(ref name, ref value)| {
+                let secret = SECRET_HEADERS.iter().any(|secret| same_header(name, secret));
+                (name.as_str(), if secret { "XXXXXXXXXXXXXXXXXXXX" } else { value.as_str() })
+            })
+            .collect();
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+
+/// A response, as returned by a transport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+
+impl Response {
+    pub fn new(status: u16, body: &str) -> Response {
+        Response {
+            status: status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    /// Add a header to the response. Mostly useful to build mocked responses.
+    pub fn with_header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Value of the first header named `name` (case insensitive), if any.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter()
+        .find(|&&(ref header_name, _)| same_header(header_name, name))
+        .map(|&(_, ref value)| value.as_str())
+}
+
+
+/// Header names are case-insensitive.
+fn same_header(name: &str, other: &str) -> bool {
+    name.to_lowercase() == other.to_lowercase()
+}
+
+
+/// Send a request and return the server's response.
+///
+/// Implementations should only fail on transport problems (connection refused, broken pipe,
+/// etc.). A response with a non-success status code is still an `Ok(Response)`; interpreting it
+/// is `GitLab`'s job.
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: &Request) -> Result<Response>;
+}
+
+
+/// Settings of the HTTP connections made by `HyperTransport`.
+///
+/// By default, there is no timeout and up to 5 idle connections per host are kept alive to be
+/// reused by later requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpConfig {
+    /// Maximum time to set up a connection, once established (see `TimeoutConnector`).
+    pub connect_timeout: Option<Duration>,
+    /// Maximum time to wait for data from the server.
+    pub read_timeout: Option<Duration>,
+    /// Maximum time to wait for the server to accept data.
+    pub write_timeout: Option<Duration>,
+    /// Maximum number of idle connections kept alive, per host. `0` disables keep-alive.
+    pub max_idle_connections: usize,
+}
+
+
+impl Default for HttpConfig {
+    fn default() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            max_idle_connections: hyper::client::pool::Config::default().max_idle,
+        }
+    }
+}
+
+
+/// Default transport, using `hyper`.
+///
+/// Connections are kept alive and reused for later requests to the same host (see `HttpConfig`).
+///
+/// The `HTTP_PROXY` environment variable is honored. When it is set, the connect timeout and the
+/// maximum number of idle connections are not applied.
+#[derive(Debug)]
+pub struct HyperTransport {
+    client: hyper::Client,
+    config: HttpConfig,
+}
+
+
+impl HyperTransport {
+    pub fn new() -> Result<HyperTransport> {
+        HyperTransport::with_config(HttpConfig::default())
+    }
+
+    /// Create a transport whose connections follow `config`.
+    pub fn with_config(config: HttpConfig) -> Result<HyperTransport> {
+        let mut client = match std::env::var("HTTP_PROXY") {
+            Ok(proxy) => {
+                let proxy: Vec<&str> = proxy.trim_left_matches("http://").split(':').collect();
+                let hostname = proxy[0].to_string();
+                let port = proxy[1].parse()
+                    .chain_err(|| format!("failure to set port to {}", proxy[1]))?;
+
+                hyper::Client::with_http_proxy(hostname, port)
+            }
+            Err(_) => {
+                let connector = TimeoutConnector { connect_timeout: config.connect_timeout };
+                // Without idle connections, connections are closed after each request instead
+                // (see `send()`), as `hyper` cannot handle an empty pool.
+                let max_idle = cmp::max(config.max_idle_connections, 1);
+                let pool_config = hyper::client::pool::Config { max_idle: max_idle };
+                hyper::net::DefaultConnector::client(connector, pool_config)
+            }
+        };
+        client.set_read_timeout(config.read_timeout);
+        client.set_write_timeout(config.write_timeout);
+
+        Ok(HyperTransport {
+            client: client,
+            config: config,
+        })
+    }
+
+    /// Settings of the connections.
+    pub fn config(&self) -> &HttpConfig {
+        &self.config
+    }
+}
+
+
+impl HttpTransport for HyperTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        let method = match request.method {
+            Method::Get => hyper::method::Method::Get,
+            Method::Post => hyper::method::Method::Post,
+            Method::Put => hyper::method::Method::Put,
+            Method::Delete => hyper::method::Method::Delete,
+        };
+
+        let mut headers = hyper::header::Headers::new();
+        for &(ref name, ref value) in &request.headers {
+            headers.set_raw(name.clone(), vec![value.clone().into_bytes()]);
+        }
+        if self.config.max_idle_connections == 0 {
+            headers.set(hyper::header::Connection::close());
+        }
+
+        let mut builder = self.client.request(method, &request.url).headers(headers);
+        if let Some(ref body) = request.body {
+            builder = builder.body(body.as_str());
+        }
+
+        let mut res: hyper::client::Response = builder.send()
+            .chain_err(|| format!("cannot send {} request", request.method))?;
+
+        // Reading the whole body allows the connection to be reused.
+        let mut body = String::new();
+        res.read_to_string(&mut body).chain_err(|| "cannot read response body")?;
+
+        Ok(Response {
+            status: res.status.to_u16(),
+            headers: res.headers
+                .iter()
+                .map(|header| (header.name().to_string(), header.value_string()))
+                .collect(),
+            body: body,
+        })
+    }
+}
+
+
+/// Plain TCP connector, bounding the setup of new connections (the TLS handshake) by
+/// `connect_timeout`.
+///
+/// Establishing the TCP connection itself is not bounded, as Rust 1.15 has no
+/// `TcpStream::connect_timeout()`: `connect_timeout` is only the read and write timeout of the
+/// connection until `hyper` applies the ones of the request.
+#[derive(Debug, Clone)]
+struct TimeoutConnector {
+    connect_timeout: Option<Duration>,
+}
+
+
+impl hyper::net::NetworkConnector for TimeoutConnector {
+    type Stream = hyper::net::HttpStream;
+
+    fn connect(&self, host: &str, port: u16, scheme: &str) -> hyper::Result<Self::Stream> {
+        let timeout = match self.connect_timeout {
+            Some(timeout) => timeout,
+            None => return hyper::net::HttpConnector.connect(host, port, scheme),
+        };
+        if scheme != "http" {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid scheme for Http")
+                .into());
+        }
+
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(hyper::net::HttpStream(stream))
+    }
+}
+
+
+/// Build a `hyper::Client` around a `TimeoutConnector`, adding TLS on top of it if `hyper` was
+/// built with TLS support (`hyper::net::DefaultConnector` being then an `HttpsConnector`).
+trait ClientWithConnector {
+    fn client(connector: TimeoutConnector,
+              pool_config: hyper::client::pool::Config)
+              -> hyper::Client;
+}
+
+
+impl ClientWithConnector for hyper::net::HttpConnector {
+    fn client(connector: TimeoutConnector,
+              pool_config: hyper::client::pool::Config)
+              -> hyper::Client {
+        hyper::Client::with_connector(hyper::client::Pool::with_connector(pool_config, connector))
+    }
+}
+
+
+impl<S> ClientWithConnector for hyper::net::HttpsConnector<S>
+    where S: hyper::net::SslClient + Default + Send + Sync + 'static,
+          S::Stream: hyper::net::NetworkStream + Send
+{
+    fn client(connector: TimeoutConnector,
+              pool_config: hyper::client::pool::Config)
+              -> hyper::Client {
+        let connector = hyper::net::HttpsConnector::with_connector(S::default(), connector);
+        hyper::Client::with_connector(hyper::client::Pool::with_connector(pool_config, connector))
+    }
+}
+
+
+/// In-memory transport replaying canned responses.
+///
+/// Responses are returned in the order they were pushed. Every request sent is recorded and can
+/// be inspected with `requests()`. Clones share the same state, so a clone can be handed to
+/// `GitLab::transport()` while the original is kept around for assertions.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    responses: Arc<Mutex<VecDeque<Response>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+
+impl MockTransport {
+    pub fn new() -> MockTransport {
+        MockTransport::default()
+    }
+
+    /// Queue a response to be returned by a future request.
+    pub fn push_response(&self, response: Response) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// All requests sent through this transport so far, oldest first.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+
+impl HttpTransport for MockTransport {
+    fn send(&self, request: &Request) -> Result<Response> {
+        self.requests.lock().unwrap().push(request.clone());
+
+        match self.responses.lock().unwrap().pop_front() {
+            Some(response) => Ok(response),
+            None => bail!(format!("no mocked response left for {} {}", request.method, request.url)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use transport::*;
+
+
+    #[test]
+    fn mock_replays_in_order() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, "first"));
+        mock.push_response(Response::new(404, "second"));
+
+        let request = Request::new(Method::Get, "https://gitlab.example.com/api/v3/version");
+        assert_eq!(mock.send(&request).unwrap().body, "first");
+        let response = mock.send(&request).unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, "second");
+        assert!(mock.send(&request).is_err());
+    }
+
+
+    #[test]
+    fn mock_records_requests() {
+        let mock = MockTransport::new();
+        mock.push_response(Response::new(200, ""));
+
+        let shared = mock.clone();
+        let mut request = Request::new(Method::Post, "https://gitlab.example.com/api/v3/projects");
+        request.body = Some(String::from("{}"));
+        shared.send(&request).unwrap();
+
+        assert_eq!(mock.requests(), vec![request]);
+    }
+
+
+    #[test]
+    fn response_header_case_insensitive() {
+        let response = Response::new(200, "").with_header("X-Next-Page", "2");
+        assert_eq!(response.header("x-next-page"), Some("2"));
+        assert_eq!(response.header("X-NEXT-PAGE"), Some("2"));
+        assert_eq!(response.header("X-Prev-Page"), None);
+    }
+
+
+    #[test]
+    fn request_debug_hides_secrets() {
+        let mut request = Request::new(Method::Get, "https://gitlab.example.com/api/v3/version");
+        request.headers.push((String::from("private-token"), String::from("0123456789abcdefghij")));
+        request.headers.push((String::from("Accept"), String::from("application/json")));
+
+        let debug = format!("{:?}", request);
+        assert!(!debug.contains("0123456789abcdefghij"));
+        assert!(debug.contains("application/json"));
+        assert_eq!(request.header("PRIVATE-TOKEN"), Some("0123456789abcdefghij"));
+    }
+
+
+    /// Serve `responses` HTTP responses on a local port, returning the URL to request and the
+    /// number of connections accepted once done.
+    fn serve(responses: usize) -> (String, thread::JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v3/version", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let mut connections = 0;
+            let mut served = 0;
+            while served < responses {
+                let stream = listener.accept().unwrap().0;
+                connections += 1;
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut stream = stream;
+                'requests: while served < responses {
+                    // Skip the request, up to the empty line ending its headers.
+                    let mut line = String::new();
+                    let mut close = false;
+                    loop {
+                        line.clear();
+                        if reader.read_line(&mut line).unwrap() == 0 {
+                            break 'requests;
+                        }
+                        if line.to_lowercase() == "connection: close\r\n" {
+                            close = true;
+                        }
+                        if line == "\r\n" {
+                            break;
+                        }
+                    }
+                    let connection = if close { "close" } else { "keep-alive" };
+                    write!(stream,
+                           "HTTP/1.1 200 OK\r\nConnection: {}\r\nContent-Length: 2\r\n\r\nok",
+                           connection)
+                        .unwrap();
+                    served += 1;
+                    if close {
+                        break;
+                    }
+                }
+            }
+            connections
+        });
+
+        (url, server)
+    }
+
+
+    #[test]
+    fn hyper_keeps_connections_alive() {
+        let (url, server) = serve(3);
+
+        let transport = HyperTransport::with_config(HttpConfig::default()).unwrap();
+        for _ in 0..3 {
+            let response = transport.send(&Request::new(Method::Get, &url)).unwrap();
+            assert_eq!(response.body, "ok");
+        }
+
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+
+    #[test]
+    fn hyper_without_idle_connections() {
+        let (url, server) = serve(2);
+
+        let config = HttpConfig { max_idle_connections: 0, ..HttpConfig::default() };
+        let transport = HyperTransport::with_config(config).unwrap();
+        for _ in 0..2 {
+            transport.send(&Request::new(Method::Get, &url)).unwrap();
+        }
+
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+
+    #[test]
+    fn hyper_read_timeout() {
+        // Accept the connection, but never answer.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v3/version", listener.local_addr().unwrap());
+        let server = thread::spawn(move || listener.accept().unwrap());
+
+        let config = HttpConfig {
+            connect_timeout: Some(Duration::from_secs(5)),
+            read_timeout: Some(Duration::from_millis(100)),
+            ..HttpConfig::default()
+        };
+        let transport = HyperTransport::with_config(config).unwrap();
+
+        let start = Instant::now();
+        assert!(transport.send(&Request::new(Method::Get, &url)).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        server.join().unwrap();
+    }
+
+
+    #[test]
+    fn method_display() {
+        assert_eq!(Method::Get.to_string(), "GET");
+        assert_eq!(Method::Post.to_string(), "POST");
+        assert_eq!(Method::Put.to_string(), "PUT");
+        assert_eq!(Method::Delete.to_string(), "DELETE");
+    }
+}